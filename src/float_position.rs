@@ -0,0 +1,101 @@
+use std::cmp::Ordering;
+use std::fmt::{Debug, Display, Formatter};
+use std::hash::{Hash, Hasher};
+use std::ops::{Add, AddAssign, Sub, SubAssign};
+
+use num_traits::{Float, Zero};
+
+/// A floating point number that is never NaN, such that it is totally ordered and can be used as
+/// a [`Position`](crate::Position).
+///
+/// Operations whose result would be NaN (such as adding opposite infinities) panic.
+///
+/// Positions are stored as sums of distances, so the same position computed in different ways may
+/// differ by rounding errors. Use lookups that take a tolerance, such as
+/// [`PointIndex::key_within`](crate::PointIndex::key_within), instead of exact matches. Distances
+/// between neighbouring points that come out slightly negative due to such errors are read as zero,
+/// so the positions are still yielded in sorted order.
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub struct FloatPosition<F: Float>(F);
+
+impl<F: Float> FloatPosition<F> {
+    /// The position at `value`, or `None` if `value` is NaN.
+    pub fn new(value: F) -> Option<Self> {
+        (!value.is_nan()).then_some(Self(value))
+    }
+
+    pub fn get(self) -> F {
+        self.0
+    }
+
+    fn from_result(value: F) -> Self {
+        Self::new(value).expect("the result of the operation is NaN")
+    }
+}
+
+impl<F: Float> Eq for FloatPosition<F> {}
+
+impl<F: Float> Hash for FloatPosition<F> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        // `-0.0 == 0.0`, so both must hash the same
+        let value = if self.0.is_zero() { F::zero() } else { self.0 };
+        value.integer_decode().hash(state);
+    }
+}
+
+impl<F: Float> PartialOrd for FloatPosition<F> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<F: Float> Ord for FloatPosition<F> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // neither value is NaN
+        self.0.partial_cmp(&other.0).unwrap()
+    }
+}
+
+impl<F: Float> Add for FloatPosition<F> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Self::from_result(self.0 + rhs.0)
+    }
+}
+
+impl<F: Float> AddAssign for FloatPosition<F> {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl<F: Float> Sub for FloatPosition<F> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        Self::from_result(self.0 - rhs.0)
+    }
+}
+
+impl<F: Float> SubAssign for FloatPosition<F> {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+impl<F: Float> Zero for FloatPosition<F> {
+    fn zero() -> Self {
+        Self(F::zero())
+    }
+
+    fn is_zero(&self) -> bool {
+        self.0.is_zero()
+    }
+}
+
+impl<F: Float + Display> Display for FloatPosition<F> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        Display::fmt(&self.0, f)
+    }
+}
@@ -28,10 +28,9 @@ impl<P: Position> BaseFrame<P> {
         self.keys.is_full()
     }
 
-    /// `distance_from_last` must be positive.
+    /// `distance_from_last` may not be negative.
     pub(crate) fn add_key(&mut self, key: PointKey, distance_from_last: P) -> usize {
-        // Distances of zero are not allowed.
-        debug_assert!(distance_from_last > zero());
+        debug_assert!(distance_from_last >= zero());
         self.check_invariants();
 
         self.distances.increase_distance(self.keys.len() - 1, distance_from_last);
@@ -39,6 +38,45 @@ impl<P: Position> BaseFrame<P> {
         self.keys.len() - 1
     }
 
+    /// Inserts `key` at `index`, `distance_from_previous` after the key at `index - 1`.
+    ///
+    /// `index` must be positive, `distance_from_previous` may not be negative and the frame may not
+    /// be full.
+    pub(crate) fn insert_key(&mut self, index: usize, key: PointKey, distance_from_previous: P) {
+        debug_assert!(distance_from_previous >= zero());
+        self.check_invariants();
+
+        self.distances.insert_index(index, self.keys.len(), distance_from_previous);
+        self.keys.insert(index, key);
+    }
+
+    /// Inserts `key` before the first key, `distance_to_next` before it.
+    ///
+    /// `distance_to_next` may not be negative and the frame may not be full.
+    pub(crate) fn insert_first_key(&mut self, key: PointKey, distance_to_next: P) {
+        debug_assert!(distance_to_next >= zero());
+        self.check_invariants();
+
+        self.distances.insert(0, distance_to_next);
+        self.keys.insert(0, key);
+    }
+
+    /// Moves the keys from `at` onwards into a new frame with the given embedding, returning
+    /// it together with the distance between the keys at `0` and `at`.
+    ///
+    /// `at` must be positive and less than the length of the frame.
+    pub(crate) fn split_off(&mut self, at: usize, embedding: Embedding) -> (Self, P) {
+        self.check_invariants();
+
+        let (distances, distance) = self.distances.split_off(at);
+        let split_off = Self {
+            distances,
+            keys: self.keys.drain(at..).collect(),
+            embedding,
+        };
+        (split_off, distance)
+    }
+
     pub(crate) fn first_key(&self) -> PointKey {
         self.check_invariants();
 
@@ -28,6 +28,16 @@ impl<P: Position> Distances<P> {
         }
     }
 
+    /// `change` may not be greater than the distance between `index` and `index + 1`.
+    pub(crate) fn decrease_distance(&mut self, index: usize, change: P) {
+        for degree in 0..DISTANCES_DEPTH {
+            if index >> degree & 1 == 0 {
+                let distance_index = index | ((1 << degree) - 1);
+                self.distances[distance_index] -= change;
+            }
+        }
+    }
+
     /// The distance between index and index + 1.
     pub(crate) fn distance(&self, index: usize) -> P {
         let mut distance = self.distances[index];
@@ -41,21 +51,77 @@ impl<P: Position> Distances<P> {
         self.splice(index..=index, 0)
     }
 
+    /// Shifts the distances from `index` onwards back by one and sets the distance between `index`
+    /// and `index + 1` to `distance`. The last distance must be zero, as it is discarded.
+    pub(crate) fn insert(&mut self, index: usize, distance: P) {
+        self.splice(index..index, 1);
+        self.increase_distance(index, distance);
+    }
+
+    /// Inserts a new index `distance_from_previous` after `index - 1`, shifting the indices from
+    /// `index` onwards back by one. `len` is the number of indices in use before the insertion, and
+    /// `index` must be positive.
+    pub(crate) fn insert_index(&mut self, index: usize, len: usize, distance_from_previous: P) {
+        if index < len {
+            let distance_to_next = self.distance(index - 1) - distance_from_previous;
+            self.decrease_distance(index - 1, distance_to_next);
+            self.insert(index, distance_to_next);
+        } else {
+            self.increase_distance(index - 1, distance_from_previous);
+        }
+    }
+
+    /// Removes `index`, such that the distance between `index - 1` and `index + 1` becomes the sum
+    /// of the distances on both sides of `index`. `len` is the number of indices in use before the
+    /// removal.
+    ///
+    /// Returns the distance between `index` and `index + 1` before the removal. If `index` is zero,
+    /// this is the distance by which the position of every remaining index decreases.
+    pub(crate) fn remove_index(&mut self, index: usize, len: usize) -> P {
+        if index + 1 == len {
+            if index > 0 {
+                // index - 1 becomes the last index, whose distance to the next index is zero
+                self.remove(index - 1);
+            }
+            return zero();
+        }
+
+        let distance_to_next = self.distance(index);
+        self.remove(index);
+        if index > 0 {
+            self.increase_distance(index - 1, distance_to_next);
+        }
+        distance_to_next
+    }
+
+    /// Splits off the distances between the indices from `at` onwards, returning them alongside the
+    /// position of `at`. `at` must be positive.
+    pub(crate) fn split_off(&mut self, at: usize) -> (Self, P) {
+        let position = self.position(at);
+        let mut split_off = *self;
+        split_off.splice(..at, 0);
+        self.splice(at - 1.., 0);
+        (split_off, position)
+    }
+
     /// Replaces the distances in `range` with `replace_with` zeroes.
+    ///
+    /// Distances that are shifted past the capacity are discarded.
     pub(crate) fn splice<R: RangeBounds<usize>>(&mut self, range: R, replace_with: usize) {
         // TODO more efficient implementation
 
-        let mut simple = self.simple();
+        let simple = self.simple();
 
         let Range { start, end: splice_end } = slice::range(range, ..simple.len());
-        let replacement_end = start + replace_with;
-        let trailing_zeroes_start = simple.len() - (splice_end - start - replace_with);
+        let replacement_end = (start + replace_with).min(simple.len());
+        let kept_len = (simple.len() - replacement_end).min(simple.len() - splice_end);
 
-        simple.copy_within(splice_end.., replacement_end);
-        simple[start..replacement_end].fill(zero());
-        simple[trailing_zeroes_start..].fill(zero());
+        let mut spliced = [zero(); DISTANCES_CAPACITY];
+        spliced[..start].copy_from_slice(&simple[..start]);
+        spliced[replacement_end..replacement_end + kept_len]
+            .copy_from_slice(&simple[splice_end..splice_end + kept_len]);
 
-        *self = Self::from_simple(simple);
+        *self = Self::from_simple(spliced);
 
         /*
         (old comment, maybe useful for efficient implementation of this function)
@@ -95,7 +161,7 @@ impl<P: Position> Distances<P> {
          */
     }
 
-    fn simple(&self) -> [P; DISTANCES_CAPACITY] {
+    pub(crate) fn simple(&self) -> [P; DISTANCES_CAPACITY] {
         let mut simple = self.distances;
         for degree in (1..DISTANCES_DEPTH).rev() {
             for index in (((1 << degree) - 1)..DISTANCES_CAPACITY).step_by(1 << degree) {
@@ -105,7 +171,7 @@ impl<P: Position> Distances<P> {
         simple
     }
 
-    fn from_simple(simple: [P; DISTANCES_CAPACITY]) -> Self {
+    pub(crate) fn from_simple(simple: [P; DISTANCES_CAPACITY]) -> Self {
         let mut distances = simple;
         for degree in 1..DISTANCES_DEPTH {
             for index in (((1 << degree) - 1)..DISTANCES_CAPACITY).step_by(1 << degree) {
@@ -135,6 +201,32 @@ impl<P: Position> Distances<P> {
     pub(crate) fn length(&self) -> P {
         self.distances[DISTANCES_CAPACITY - 1]
     }
+
+    /// The greatest index not exceeding `last_index` whose position satisfies `predicate`, together
+    /// with that position.
+    ///
+    /// `predicate` must hold for a position of zero, and once it fails for some position, it must
+    /// fail for all greater positions.
+    pub(crate) fn search(&self, last_index: usize, predicate: impl Fn(P) -> bool) -> (usize, P) {
+        let mut index = 0;
+        let mut position = zero();
+
+        // binary lifting: self.distances[index + (1 << degree) - 1] is the distance between index
+        // and index + (1 << degree) while index is a multiple of 1 << (degree + 1)
+        for degree in (0..DISTANCES_DEPTH).rev() {
+            let next_index = index + (1 << degree);
+            if next_index > last_index {
+                continue;
+            }
+            let next_position = position + self.distances[next_index - 1];
+            if predicate(next_position) {
+                index = next_index;
+                position = next_position;
+            }
+        }
+
+        (index, position)
+    }
 }
 
 #[cfg(test)]
@@ -199,5 +291,45 @@ mod tests {
         let mut distances = Distances::from_simple([1u32; DISTANCES_CAPACITY]);
         distances.splice(HALF.., HALF);
         assert_eq!(distances, first_half);
+
+        let mut distances = Distances::from_simple([1u32; DISTANCES_CAPACITY]);
+        distances.splice(..0, HALF);
+        assert_eq!(distances, second_half);
+    }
+
+    #[test]
+    fn test_insert_and_remove_index() {
+        let mut distances = Distances::<u32>::new();
+        distances.insert_index(1, 1, 3);
+        distances.insert_index(1, 2, 1);
+        distances.insert_index(3, 3, 4);
+        assert_eq!(distances.simple()[..3], [1, 2, 4]);
+
+        let mut removed = distances;
+        assert_eq!(removed.remove_index(1, 4), 2);
+        assert_eq!(removed.simple()[..3], [3, 4, 0]);
+        assert_eq!(removed.remove_index(2, 3), 0);
+        assert_eq!(removed.simple()[..2], [3, 0]);
+        assert_eq!(removed.remove_index(0, 2), 3);
+        assert_eq!(removed.simple()[..1], [0]);
+
+        let (split_off, distance) = distances.split_off(2);
+        assert_eq!(distance, 3);
+        assert_eq!(distances.simple()[..2], [1, 0]);
+        assert_eq!(split_off.simple()[..2], [4, 0]);
+    }
+
+    #[test]
+    fn test_search() {
+        let distances = Distances::from_simple([1u32, 2, 3, 4]);
+        for position in 0..12 {
+            let expected = (0..DISTANCES_CAPACITY)
+                .rev()
+                .find(|&index| distances.position(index) <= position)
+                .unwrap();
+            assert_eq!(distances.search(3, |p| p <= position), (expected, distances.position(expected)));
+        }
+        assert_eq!(distances.search(2, |p| p <= 100), (2, 3));
+        assert_eq!(distances.search(4, |p| p < 6), (2, 3));
     }
 }
@@ -6,6 +6,9 @@ use crate::{Position, Frame, Distances, Embedding, FrameKey, FRAME_CAPACITY};
 #[derive(Debug, Eq, PartialEq, Clone)]
 pub(crate) struct MetaFrame<P: Position> {
     pub(crate) distances: Distances<P>,
+    /// The distances between the frames in terms of points, i.e. the distance between `index` and
+    /// `index + 1` is the number of points in the frame at `index`.
+    pub(crate) counts: Distances<usize>,
     /// May not be empty.
     pub(crate) frames: ArrayVec<FrameKey, FRAME_CAPACITY>,
     pub(crate) level: usize,
@@ -16,6 +19,7 @@ impl<P: Position> MetaFrame<P> {
     pub(crate) fn new_with_frame(key: FrameKey, level: usize, embedding: Embedding) -> (Self, usize) {
         (Self {
             distances: Distances::new(),
+            counts: Distances::new(),
             frames: ArrayVec::from_iter(once(key)),
             level,
             embedding,
@@ -30,17 +34,65 @@ impl<P: Position> MetaFrame<P> {
         self.frames.is_full()
     }
 
-    /// `distance_from_last` must be positive.
-    pub(crate) fn add_frame(&mut self, key: FrameKey, distance_from_last: P) -> usize {
-        // Distances of zero are not allowed.
-        debug_assert!(distance_from_last > zero());
+    /// `distance_from_last` may not be negative. `count_of_last` is the number of points in the
+    /// current last frame.
+    pub(crate) fn add_frame(&mut self, key: FrameKey, distance_from_last: P, count_of_last: usize) -> usize {
+        debug_assert!(distance_from_last >= zero());
         self.check_invariants();
 
         self.distances.increase_distance(self.frames.len() - 1, distance_from_last);
+        self.counts.increase_distance(self.frames.len() - 1, count_of_last);
         self.frames.push(key);
         self.frames.len() - 1
     }
 
+    /// Inserts `key` at `index`, `distance_from_previous` after the frame at `index - 1`.
+    ///
+    /// `count_of_previous` is the number of points in the frame at `index - 1`.
+    ///
+    /// `index` must be positive, `distance_from_previous` may not be negative and the frame may not
+    /// be full.
+    pub(crate) fn insert_frame(&mut self, index: usize, key: FrameKey, distance_from_previous: P, count_of_previous: usize) {
+        debug_assert!(distance_from_previous >= zero());
+        self.check_invariants();
+
+        self.distances.insert_index(index, self.frames.len(), distance_from_previous);
+        self.counts.insert_index(index, self.frames.len(), count_of_previous);
+        self.frames.insert(index, key);
+    }
+
+    /// Inserts `key` before the first frame, `distance_to_next` before it. `count` is the number of
+    /// points in the inserted frame.
+    ///
+    /// `distance_to_next` may not be negative and the frame may not be full.
+    pub(crate) fn insert_first_frame(&mut self, key: FrameKey, distance_to_next: P, count: usize) {
+        debug_assert!(distance_to_next >= zero());
+        self.check_invariants();
+
+        self.distances.insert(0, distance_to_next);
+        self.counts.insert(0, count);
+        self.frames.insert(0, key);
+    }
+
+    /// Moves the frames from `at` onwards into a new frame with the given embedding, returning
+    /// it together with the distance between the frames at `0` and `at`.
+    ///
+    /// `at` must be positive and less than the length of the frame.
+    pub(crate) fn split_off(&mut self, at: usize, embedding: Embedding) -> (Self, P) {
+        self.check_invariants();
+
+        let (distances, distance) = self.distances.split_off(at);
+        let (counts, _) = self.counts.split_off(at);
+        let split_off = Self {
+            distances,
+            counts,
+            frames: self.frames.drain(at..).collect(),
+            level: self.level,
+            embedding,
+        };
+        (split_off, distance)
+    }
+
     pub(crate) fn first_frame(&self) -> FrameKey {
         self.check_invariants();
 
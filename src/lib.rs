@@ -7,6 +7,7 @@
 #![allow(unused_doc_comments)]
 
 mod trait_aliases;
+mod float_position;
 mod point_list;
 mod frame;
 mod span_tree;
@@ -15,9 +16,16 @@ mod distances;
 #[doc(inline)]
 pub use {
     point_list::PointList,
+    point_list::PointIndex,
     point_list::PointKey,
+    point_list::insert_element::InsertError,
+    point_list::shift::ShiftError,
+    point_list::drain::RangeGap,
+    point_list::entry::{Entry, OccupiedEntry, VacantEntry},
+    point_list::cursor::{Cursor, CursorMut},
     trait_aliases::Element,
     trait_aliases::Position,
+    float_position::FloatPosition,
 };
 
 #[allow(unused_imports)]
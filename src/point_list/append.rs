@@ -0,0 +1,152 @@
+use slotmap::SecondaryMap;
+use num_traits::zero;
+use crate::{EitherFrame, Element, Embedding, Frame, FrameKey, IndexInFrame, InsertError, PointIndex, PointKey, PointList, Position};
+
+impl<P: Position> PointIndex<P> {
+    /// Moves the tree at `root` out of `from` into the frames of this index without connecting it
+    /// to its root, giving its points new keys. Returns the root of the moved tree together with
+    /// the new keys of its points, by their keys in `from`.
+    ///
+    /// `root` may not be embedded in a meta frame. Only the frames and points of the tree are
+    /// touched, the rest of `from` is left as it is.
+    pub(crate) fn move_tree(&mut self, from: &mut Self, root: FrameKey) -> (FrameKey, SecondaryMap<PointKey, PointKey>) {
+        let mut frame_keys = SecondaryMap::new();
+        for frame_key in from.frames_below(root).keys() {
+            frame_keys.insert(frame_key, self.frames.insert(from.frames.remove(frame_key).unwrap()));
+        }
+
+        let mut point_keys = SecondaryMap::new();
+        for &frame_key in frame_keys.values() {
+            let frame = &mut self.frames[frame_key];
+            if let Embedding::InMetaFrame(IndexInFrame { frame, .. }) = frame.embedding_mut() {
+                *frame = frame_keys[*frame];
+            }
+            match frame {
+                EitherFrame::Meta(frame) => {
+                    for child in &mut frame.frames {
+                        *child = frame_keys[*child];
+                    }
+                }
+                EitherFrame::Base(frame) => {
+                    for (index, point_key) in frame.keys.iter_mut().enumerate() {
+                        from.point_indices.remove(*point_key);
+                        let new_point_key = self.point_indices.insert(IndexInFrame::new(frame_key, index));
+                        point_keys.insert(*point_key, new_point_key);
+                        *point_key = new_point_key;
+                    }
+                }
+            }
+        }
+
+        (frame_keys[root], point_keys)
+    }
+
+    /// Connects the tree at `other_root`, which must be part of the frames of this index without
+    /// being connected to its root, behind the points of this index, `gap` after the last one. The
+    /// tree holds `other_len` points and `other_length` is the distance between its first and last
+    /// one.
+    ///
+    /// The index may not be empty. The trees are joined at the level of the lower one.
+    pub(crate) fn join(&mut self, other_root: FrameKey, other_len: usize, other_length: P, gap: P) {
+        assert!(gap >= zero());
+
+        let root_key = self.root.unwrap();
+        let level = self.frames[root_key].level();
+        let other_level = self.frames[other_root].level();
+        if level >= other_level {
+            // find the lowest frame on the right spine that can take a frame of other_level
+            let mut frame_key = root_key;
+            while self.frames[frame_key].level() > other_level + 1 {
+                frame_key = self.frames[frame_key].unwrap_meta().last_frame();
+            }
+            while self.frames[frame_key].level() == other_level || self.frames[frame_key].unwrap_meta().is_full() {
+                frame_key = match self.frames[frame_key].embedding() {
+                    Embedding::InMetaFrame(IndexInFrame { frame, .. }) => frame,
+                    Embedding::InList => self.raise_root(),
+                };
+            }
+
+            let meta_level = self.frames[frame_key].level();
+            let current_frame = self.wrap_frame(other_root, meta_level - 1);
+            let last_frame = self.frames[frame_key].unwrap_meta().last_frame();
+            let length_of_last_frame = self.length_of(last_frame);
+            let count_of_last_frame = self.count_of(last_frame);
+            let frame = self.frames[frame_key].unwrap_meta_mut();
+            let index = frame.add_frame(current_frame, length_of_last_frame + gap, count_of_last_frame);
+            self.reindex(frame_key, index);
+            // frame_key is on the right spine, so the frames containing it don't store its count
+        } else {
+            // find the lowest frame on the left spine of other that can take a frame of level
+            self.root = Some(other_root);
+            let mut frame_key = other_root;
+            while self.frames[frame_key].level() > level + 1 {
+                frame_key = self.frames[frame_key].unwrap_meta().first_frame();
+            }
+            while self.frames[frame_key].unwrap_meta().is_full() {
+                frame_key = match self.frames[frame_key].embedding() {
+                    Embedding::InMetaFrame(IndexInFrame { frame, .. }) => frame,
+                    Embedding::InList => self.raise_root(),
+                };
+            }
+
+            let meta_level = self.frames[frame_key].level();
+            let current_frame = self.wrap_frame(root_key, meta_level - 1);
+            let distance_to_next = self.end - self.start + gap;
+            self.frames[frame_key].unwrap_meta_mut()
+                .insert_first_frame(current_frame, distance_to_next, self.len);
+            self.reindex(frame_key, 0);
+            self.increase_counts(frame_key, self.len);
+
+            self.first_point_moved_earlier(frame_key, distance_to_next);
+        }
+
+        self.end += gap + other_length;
+        self.len += other_len;
+        self.cache_last_base_frame();
+    }
+
+    /// Moves the points of `other` behind the points of this index, keeping the distances between
+    /// them. The first point of `other` ends up `gap` after the last point of this index, or at its
+    /// own position if this index is empty.
+    ///
+    /// The two frame trees are joined at the level of the lower one, but the points of `other`
+    /// have to be given new keys. Returns the new keys, by the keys the points had in `other`, or
+    /// [`InsertError::NegativeDistance`] without changing this index if `gap` is negative.
+    pub fn append(&mut self, mut other: Self, gap: P) -> Result<SecondaryMap<PointKey, PointKey>, InsertError> {
+        if gap < zero() {
+            return Err(InsertError::NegativeDistance);
+        }
+        let Some(other_root) = other.root else {
+            return Ok(SecondaryMap::new());
+        };
+
+        let (other_start, other_end, other_len) = (other.start, other.end, other.len);
+        let (other_root, point_keys) = self.move_tree(&mut other, other_root);
+
+        if self.is_empty() {
+            self.root = Some(other_root);
+            self.start = other_start;
+            self.end = other_end;
+            self.len = other_len;
+            self.cache_last_base_frame();
+        } else {
+            self.join(other_root, other_len, other_end - other_start, gap);
+        }
+
+        Ok(point_keys)
+    }
+}
+
+impl<P: Position, E: Element> PointList<P, E> {
+    /// Moves the points of `other` behind the points of this list, keeping their elements and the
+    /// distances between them, see [`PointIndex::append`]. Returns the new keys, by the keys the
+    /// points had in `other`, or [`InsertError::NegativeDistance`] if `gap` is negative.
+    pub fn append(&mut self, other: Self, gap: P) -> Result<SecondaryMap<PointKey, PointKey>, InsertError> {
+        let Self { index, mut elements } = other;
+        let point_keys = self.index.append(index, gap)?;
+        for (point_key, &new_point_key) in &point_keys {
+            self.elements.insert(new_point_key, elements.remove(point_key).unwrap());
+        }
+        Ok(point_keys)
+    }
+}
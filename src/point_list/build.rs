@@ -0,0 +1,141 @@
+use num_traits::zero;
+use slotmap::Key;
+use crate::{BaseFrame, Element, Embedding, FrameKey, IndexInFrame, MetaFrame, PointIndex, PointKey, PointList, Position, FRAME_CAPACITY};
+
+/// The number of keys or frames put into each frame when building, such that a key or frame can be
+/// inserted into every frame before it has to be split.
+const BUILD_FILL: usize = FRAME_CAPACITY - 1;
+
+impl<P: Position> PointIndex<P> {
+    /// Replaces the frames of the index by new ones holding the given keys at the given positions,
+    /// which must be sorted by position. Keys sharing a position keep their order. The keys must
+    /// already be registered, their indices in frame are overwritten.
+    ///
+    /// Base frames are filled up to [`BUILD_FILL`] and the meta frames are built level by level on
+    /// top of them, which takes linear time.
+    pub(crate) fn build_frames(&mut self, points: impl IntoIterator<Item = (PointKey, P)>) {
+        self.frames.clear();
+        self.root = None;
+        self.last_base_frame = None;
+        self.start = zero();
+        self.end = zero();
+        self.len = 0;
+
+        // the key, the position of the first point and the number of points of each frame on the
+        // level that is currently being built
+        let mut frames: Vec<(FrameKey, P, usize)> = vec![];
+        for (point_key, position) in points {
+            match frames.last_mut() {
+                Some((frame_key, _, count)) if *count < BUILD_FILL => {
+                    assert!(position >= self.end);
+
+                    let frame = self.frames[*frame_key].unwrap_base_mut();
+                    let index = frame.add_key(point_key, position - self.end);
+                    self.point_indices[point_key] = IndexInFrame::new(*frame_key, index);
+                    *count += 1;
+                }
+                last_frame => {
+                    assert!(last_frame.is_none() || position >= self.end);
+
+                    let (base_frame, index) = BaseFrame::new_with_key(point_key, Embedding::InList);
+                    let base_frame_key = self.frames.insert(base_frame.into());
+                    self.point_indices[point_key] = IndexInFrame::new(base_frame_key, index);
+                    frames.push((base_frame_key, position, 1));
+                }
+            }
+            self.end = position;
+            self.len += 1;
+        }
+
+        let Some(&(_, start, _)) = frames.first() else {
+            return;
+        };
+        self.start = start;
+        self.last_base_frame = frames.last().map(|&(frame_key, _, _)| frame_key);
+
+        let mut level = 0;
+        while frames.len() > 1 {
+            level += 1;
+            frames = frames.chunks(BUILD_FILL).map(|chunk| {
+                let (first_frame, first_position, _) = chunk[0];
+                let (mut meta_frame, _) = MetaFrame::new_with_frame(first_frame, level, Embedding::InList);
+                for window in chunk.windows(2) {
+                    let [(_, previous_position, previous_count), (frame_key, position, _)] = *window else {
+                        unreachable!()
+                    };
+                    meta_frame.add_frame(frame_key, position - previous_position, previous_count);
+                }
+                let meta_frame_key = self.frames.insert(meta_frame.into());
+                self.reindex(meta_frame_key, 0);
+                (meta_frame_key, first_position, chunk.iter().map(|&(_, _, count)| count).sum())
+            }).collect();
+        }
+        self.root = Some(frames[0].0);
+    }
+
+    /// Builds an index from points given by their distance from the previous point (or, for the
+    /// first point, its position). Returns the index together with the keys of the points in the
+    /// order they were given.
+    ///
+    /// This takes linear time, see [`Self::build_frames`].
+    pub fn from_distances(distances: impl IntoIterator<Item = P>) -> (Self, Vec<PointKey>) {
+        let mut index = Self::new();
+
+        let mut position = zero();
+        let points: Vec<_> = distances.into_iter().map(|distance| {
+            position += distance;
+            // the index in frame is set when the frames are built
+            let point_key = index.point_indices.insert(IndexInFrame::new(FrameKey::null(), 0));
+            (point_key, position)
+        }).collect();
+        index.build_frames(points.iter().copied());
+
+        (index, points.into_iter().map(|(point_key, _)| point_key).collect())
+    }
+
+    /// Builds an index from points given by their position, which must be sorted. Returns the
+    /// index together with the keys of the points in the order they were given.
+    ///
+    /// Panics if the positions are not sorted, before subtracting any of them. See
+    /// [`Self::from_distances`].
+    pub fn from_sorted(positions: impl IntoIterator<Item = P>) -> (Self, Vec<PointKey>) {
+        let mut previous_position = None;
+        Self::from_distances(positions.into_iter().map(|position| {
+            let distance = match previous_position {
+                Some(previous_position) => {
+                    assert!(position >= previous_position, "the positions passed to from_sorted must be sorted");
+                    position - previous_position
+                }
+                None => position,
+            };
+            previous_position = Some(position);
+            distance
+        }))
+    }
+}
+
+impl<P: Position, E: Element> PointList<P, E> {
+    /// Builds a list from points given by their distance from the previous point (or, for the
+    /// first point, its position) and their element. Returns the list together with the keys of
+    /// the points in the order they were given.
+    ///
+    /// This takes linear time, see [`PointIndex::from_distances`].
+    pub fn from_distances(points: impl IntoIterator<Item = (P, E)>) -> (Self, Vec<PointKey>) {
+        let (distances, elements): (Vec<_>, Vec<_>) = points.into_iter().unzip();
+        let (index, point_keys) = PointIndex::from_distances(distances);
+        let elements = point_keys.iter().copied().zip(elements).collect();
+        (Self { index, elements }, point_keys)
+    }
+
+    /// Builds a list from points given by their position and their element, which must be sorted
+    /// by position. Returns the list together with the keys of the points in the order they were
+    /// given.
+    ///
+    /// See [`PointIndex::from_sorted`].
+    pub fn from_sorted(points: impl IntoIterator<Item = (P, E)>) -> (Self, Vec<PointKey>) {
+        let (positions, elements): (Vec<_>, Vec<_>) = points.into_iter().unzip();
+        let (index, point_keys) = PointIndex::from_sorted(positions);
+        let elements = point_keys.iter().copied().zip(elements).collect();
+        (Self { index, elements }, point_keys)
+    }
+}
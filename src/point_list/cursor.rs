@@ -0,0 +1,202 @@
+use crate::{Element, IndexInFrame, InsertError, PointKey, PointList, Position, ShiftError};
+
+/// Points at a point of a [`PointList`], or at a "ghost" position past its last and before its
+/// first point, and can be moved between neighbouring points without searching from the root.
+///
+/// Moving past either end of the list moves the cursor to the ghost position, and moving on from
+/// there wraps around to the other end. Cursors are `Copy`, so a position can be saved and
+/// returned to later.
+pub struct Cursor<'a, P: Position, E: Element> {
+    list: &'a PointList<P, E>,
+    /// The index in frame and position of the current point, `None` at the ghost position.
+    current: Option<(IndexInFrame, P)>,
+}
+
+// not derived, as that would require the elements to be `Copy`
+impl<P: Position, E: Element> Clone for Cursor<'_, P, E> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<P: Position, E: Element> Copy for Cursor<'_, P, E> {}
+
+/// A [`Cursor`] that can also change the list around the current point.
+pub struct CursorMut<'a, P: Position, E: Element> {
+    list: &'a mut PointList<P, E>,
+    /// The index in frame and position of the current point, `None` at the ghost position.
+    current: Option<(IndexInFrame, P)>,
+}
+
+impl<'a, P: Position, E: Element> Cursor<'a, P, E> {
+    pub fn key(&self) -> Option<PointKey> {
+        self.current.map(|(index_in_frame, _)| self.list.index.key_of(index_in_frame))
+    }
+
+    pub fn position(&self) -> Option<P> {
+        self.current.map(|(_, position)| position)
+    }
+
+    pub fn element(&self) -> Option<&'a E> {
+        self.key().map(|key| &self.list.elements[key])
+    }
+
+    pub fn move_next(&mut self) {
+        self.current = self.list.index.next_after(self.current);
+    }
+
+    pub fn move_prev(&mut self) {
+        self.current = self.list.index.previous_before(self.current);
+    }
+
+    /// Moves to the first point at or after `position`, or to the ghost position if there is none.
+    pub fn seek(&mut self, position: P) {
+        self.current = self.list.first_at_or_after(position);
+    }
+}
+
+impl<'a, P: Position, E: Element> CursorMut<'a, P, E> {
+    pub fn key(&self) -> Option<PointKey> {
+        self.as_cursor().key()
+    }
+
+    pub fn position(&self) -> Option<P> {
+        self.as_cursor().position()
+    }
+
+    pub fn element_mut(&mut self) -> Option<&mut E> {
+        let key = self.key()?;
+        Some(&mut self.list.elements[key])
+    }
+
+    /// A read-only cursor at the same point.
+    pub fn as_cursor(&self) -> Cursor<'_, P, E> {
+        Cursor { list: self.list, current: self.current }
+    }
+
+    /// Moves to where `move_cursor` moves a read-only cursor at the same point.
+    fn move_like(&mut self, move_cursor: impl FnOnce(&mut Cursor<'_, P, E>)) {
+        let mut cursor = self.as_cursor();
+        move_cursor(&mut cursor);
+        self.current = cursor.current;
+    }
+
+    pub fn move_next(&mut self) {
+        self.move_like(|cursor| cursor.move_next());
+    }
+
+    pub fn move_prev(&mut self) {
+        self.move_like(|cursor| cursor.move_prev());
+    }
+
+    /// Moves to the first point at or after `position`, or to the ghost position if there is none.
+    pub fn seek(&mut self, position: P) {
+        self.move_like(|cursor| cursor.seek(position));
+    }
+
+    /// Runs `change` on the list and the key of the current point, after which the index in frame
+    /// of the current point is looked up again, as the change may have moved it to another frame.
+    fn change_around<T>(
+        &mut self,
+        change: impl FnOnce(&mut PointList<P, E>, PointKey) -> T,
+    ) -> Option<T> {
+        let key = self.key()?;
+        let result = change(self.list, key);
+        let (index_in_frame, _) = self.current.as_mut().unwrap();
+        *index_in_frame = self.list.index.point_indices[key];
+        Some(result)
+    }
+
+    /// Inserts `element` `distance` after the current point, see [`PointList::insert_after`]. The
+    /// cursor stays at the current point.
+    ///
+    /// Fails with [`InsertError::UnknownKey`] at the ghost position.
+    pub fn insert_after(&mut self, distance: P, element: E) -> Result<PointKey, InsertError> {
+        self.change_around(|list, key| list.insert_after(key, distance, element))
+            .unwrap_or(Err(InsertError::UnknownKey))
+    }
+
+    /// Inserts `element` `distance` before the current point, see [`PointList::insert_before`].
+    /// The cursor stays at the current point.
+    ///
+    /// Fails with [`InsertError::UnknownKey`] at the ghost position.
+    pub fn insert_before(&mut self, distance: P, element: E) -> Result<PointKey, InsertError> {
+        self.change_around(|list, key| list.insert_before(key, distance, element))
+            .unwrap_or(Err(InsertError::UnknownKey))
+    }
+
+    /// Sets the distance between the current point and the next one, see
+    /// [`PointList::set_distance_to_next`].
+    ///
+    /// Fails with [`ShiftError::UnknownKey`] at the ghost position.
+    pub fn set_distance_to_next(&mut self, distance: P) -> Result<P, ShiftError> {
+        self.change_around(|list, key| list.set_distance_to_next(key, distance))
+            .unwrap_or(Err(ShiftError::UnknownKey))
+    }
+
+    /// Sets the distance between the current point and the previous one, moving the current point,
+    /// see [`PointList::set_distance_to_prev`].
+    ///
+    /// Fails with [`ShiftError::UnknownKey`] at the ghost position.
+    pub fn set_distance_to_prev(&mut self, distance: P) -> Result<P, ShiftError> {
+        let old_distance = self.change_around(|list, key| list.set_distance_to_prev(key, distance))
+            .unwrap_or(Err(ShiftError::UnknownKey))?;
+        let (_, position) = self.current.as_mut().unwrap();
+        *position = *position + distance - old_distance;
+        Ok(old_distance)
+    }
+
+    /// Removes the current point and moves the cursor to the next one. Returns the key and element
+    /// of the removed point, or `None` at the ghost position.
+    pub fn remove_current(&mut self) -> Option<(PointKey, E)> {
+        let key = self.key()?;
+        let next = self.list.index.next_after(self.current)
+            .map(|(index_in_frame, position)| (self.list.index.key_of(index_in_frame), position));
+        let element = self.list.remove_element(key).unwrap();
+        self.current = next.map(|(next_key, position)| (self.list.index.point_indices[next_key], position));
+        Some((key, element))
+    }
+}
+
+impl<P: Position, E: Element> PointList<P, E> {
+    /// The index in frame and position of the first point at or after `position`.
+    fn first_at_or_after(&self, position: P) -> Option<(IndexInFrame, P)> {
+        self.index.next_after(self.index.find_last(|other_position| other_position < position))
+    }
+
+    /// A cursor at the first point, or at the ghost position if the list is empty.
+    pub fn cursor_front(&self) -> Cursor<'_, P, E> {
+        Cursor { list: self, current: self.index.next_after(None) }
+    }
+
+    /// A cursor at the last point, or at the ghost position if the list is empty.
+    pub fn cursor_back(&self) -> Cursor<'_, P, E> {
+        Cursor { list: self, current: self.index.previous_before(None) }
+    }
+
+    /// A cursor at the point at `key`, if there is one.
+    pub fn cursor(&self, key: PointKey) -> Option<Cursor<'_, P, E>> {
+        let index_in_frame = *self.index.point_indices.get(key)?;
+        let position = self.position(key)?;
+        Some(Cursor { list: self, current: Some((index_in_frame, position)) })
+    }
+
+    /// A mutable cursor at the first point, or at the ghost position if the list is empty.
+    pub fn cursor_front_mut(&mut self) -> CursorMut<'_, P, E> {
+        let current = self.index.next_after(None);
+        CursorMut { list: self, current }
+    }
+
+    /// A mutable cursor at the last point, or at the ghost position if the list is empty.
+    pub fn cursor_back_mut(&mut self) -> CursorMut<'_, P, E> {
+        let current = self.index.previous_before(None);
+        CursorMut { list: self, current }
+    }
+
+    /// A mutable cursor at the point at `key`, if there is one.
+    pub fn cursor_mut(&mut self, key: PointKey) -> Option<CursorMut<'_, P, E>> {
+        let index_in_frame = *self.index.point_indices.get(key)?;
+        let position = self.position(key)?;
+        Some(CursorMut { list: self, current: Some((index_in_frame, position)) })
+    }
+}
@@ -1,14 +1,27 @@
 use std::fmt::{Debug, Formatter};
 use std::cmp::Ordering;
-use slotmap::Key;
-use crate::{Element, PointList, Position};
+use slotmap::{Key, SecondaryMap};
+use crate::{Element, PointIndex, PointKey, PointList, Position};
 use crate::frame::distances::{Distances, DISTANCES_DEPTH};
 use crate::frame::{EitherFrame, Frame, FRAME_CAPACITY};
 
+impl<P: Position> Debug for PointIndex<P> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        self.fmt_with_elements::<()>(f, "PointIndex", None)
+    }
+}
+
 impl<P: Position, E: Element> Debug for PointList<P, E> {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        self.index.fmt_with_elements(f, "PointList", Some(&self.elements))
+    }
+}
+
+impl<P: Position> PointIndex<P> {
+    /// Writes the structure of the index, listing the elements of the points if there are any.
+    fn fmt_with_elements<E: Element>(&self, f: &mut Formatter<'_>, name: &str, elements: Option<&SecondaryMap<PointKey, E>>) -> std::fmt::Result {
         if self.is_empty() {
-            return f.write_str("[empty PointList]");
+            return write!(f, "[empty {}]", name);
         }
 
         writeln!(f, "len: {}", self.len)?;
@@ -67,7 +80,11 @@ impl<P: Position, E: Element> Debug for PointList<P, E> {
         }
 
         for (key, &index_in_frame) in &self.point_indices {
-            writeln!(f, "{:>width$?}: {:?} ({:?}/{})", key.data(), self.element(key).unwrap(), index_in_frame.frame.data(), index_in_frame.index, width = width)?;
+            write!(f, "{:>width$?}: ", key.data(), width = width)?;
+            if let Some(elements) = elements {
+                write!(f, "{:?} ", elements[key])?;
+            }
+            writeln!(f, "({:?}/{})", index_in_frame.frame.data(), index_in_frame.index)?;
         }
 
         Ok(())
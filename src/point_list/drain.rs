@@ -0,0 +1,122 @@
+use std::ops::{Bound, RangeBounds};
+use num_traits::zero;
+use crate::{Element, PointIndex, PointKey, PointList, Position};
+
+/// What happens to the gap that is left behind by the points removed from a range.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum RangeGap {
+    /// The points after the range keep their positions.
+    Preserve,
+    /// The points after the range are moved forward by the extent of the range, such that the range
+    /// is cut out. The extent is clamped to the points next to the range, and if there is no point
+    /// before the range, it starts at the first removed point.
+    Collapse,
+}
+
+impl<P: Position> PointIndex<P> {
+    /// Removes the points in `range` and returns their former keys and positions in positional
+    /// order. The points are removed right away, even if the returned iterator is not consumed.
+    ///
+    /// The frames are only cut at the boundaries of the range, after which the trees before and
+    /// after the range are joined. Only the keys of the removed points are touched one by one.
+    pub fn drain_range(&mut self, range: impl RangeBounds<P>, gap: RangeGap) -> impl Iterator<Item = (PointKey, P)> {
+        let (start_bound, end_bound) = (range.start_bound().cloned(), range.end_bound().cloned());
+        let drained: Vec<_> = self.range(range).collect();
+        let (Some(&(first_key, first_position)), Some(&(last_key, last_position))) = (drained.first(), drained.last()) else {
+            return vec![].into_iter();
+        };
+
+        let end = self.end;
+        let previous = self.previous_of(self.point_indices[first_key]);
+        let next = self.next_of(self.point_indices[last_key]);
+
+        // cut off the points after the range first, such that the indices of the earlier points
+        // stay valid
+        let right = next.map(|(_, distance_to_next)| {
+            let (right_root, _) = self.cut_after(self.point_indices[last_key]);
+            (right_root, last_position + distance_to_next)
+        });
+        let (middle_root, left) = match previous {
+            Some((previous, distance_to_previous)) => {
+                let (middle_root, _) = self.cut_after(previous);
+                (middle_root, Some((self.root.unwrap(), first_position - distance_to_previous)))
+            }
+            None => (self.root.unwrap(), None),
+        };
+
+        for frame_key in self.frames_below(middle_root).keys() {
+            self.frames.remove(frame_key);
+        }
+        for &(point_key, _) in &drained {
+            self.point_indices.remove(point_key);
+        }
+        self.len -= drained.len();
+
+        let shift = match (gap, left, right) {
+            (RangeGap::Collapse, left, Some((_, right_start))) => {
+                let range_start = match start_bound {
+                    Bound::Included(start) | Bound::Excluded(start) => start,
+                    Bound::Unbounded => first_position,
+                };
+                let range_end = match end_bound {
+                    Bound::Included(end) | Bound::Excluded(end) => end,
+                    Bound::Unbounded => last_position,
+                };
+                let range_start = match left {
+                    Some((_, left_end)) => range_start.max(left_end),
+                    None => first_position,
+                };
+                range_end.min(right_start) - range_start
+            }
+            _ => zero(),
+        };
+        match (left, right) {
+            (Some((left_root, left_end)), Some((right_root, right_start))) => {
+                self.root = Some(self.lower(left_root));
+                self.end = left_end;
+                let right_root = self.lower(right_root);
+                let right_len = self.count_of(right_root);
+                self.len -= right_len;
+                self.join(right_root, right_len, end - right_start, right_start - shift - left_end);
+            }
+            (Some((left_root, left_end)), None) => {
+                self.root = Some(self.lower(left_root));
+                self.end = left_end;
+            }
+            (None, Some((right_root, right_start))) => {
+                self.root = Some(self.lower(right_root));
+                self.start = right_start - shift;
+                self.end = end - shift;
+            }
+            (None, None) => {
+                self.root = None;
+                self.start = zero();
+                self.end = zero();
+            }
+        }
+        self.cache_last_base_frame();
+
+        drained.into_iter()
+    }
+
+    /// Removes the points in `range`, see [`Self::drain_range`].
+    pub fn remove_range(&mut self, range: impl RangeBounds<P>, gap: RangeGap) {
+        let _ = self.drain_range(range, gap);
+    }
+}
+
+impl<P: Position, E: Element> PointList<P, E> {
+    /// Removes the points in `range` and returns them in positional order, together with their
+    /// former keys and positions, see [`PointIndex::drain_range`].
+    pub fn drain_range(&mut self, range: impl RangeBounds<P>, gap: RangeGap) -> impl Iterator<Item = (PointKey, P, E)> {
+        let drained: Vec<_> = self.index.drain_range(range, gap)
+            .map(|(point_key, position)| (point_key, position, self.elements.remove(point_key).unwrap()))
+            .collect();
+        drained.into_iter()
+    }
+
+    /// Removes the points in `range`, see [`PointIndex::drain_range`].
+    pub fn remove_range(&mut self, range: impl RangeBounds<P>, gap: RangeGap) {
+        let _ = self.drain_range(range, gap);
+    }
+}
@@ -0,0 +1,111 @@
+use crate::{Element, IndexInFrame, PointKey, PointList, Position};
+
+/// A position in a [`PointList`], which is either occupied by a point or vacant.
+///
+/// Constructed by [`PointList::entry`].
+pub enum Entry<'a, P: Position, E: Element> {
+    Occupied(OccupiedEntry<'a, P, E>),
+    Vacant(VacantEntry<'a, P, E>),
+}
+
+/// A position in a [`PointList`] that is occupied by at least one point.
+pub struct OccupiedEntry<'a, P: Position, E: Element> {
+    list: &'a mut PointList<P, E>,
+    /// The first point at the position.
+    key: PointKey,
+    position: P,
+}
+
+/// A position in a [`PointList`] without any points.
+pub struct VacantEntry<'a, P: Position, E: Element> {
+    list: &'a mut PointList<P, E>,
+    position: P,
+    /// The index in frame and position of the last point before the position, if there is one.
+    previous: Option<(IndexInFrame, P)>,
+}
+
+impl<'a, P: Position, E: Element> Entry<'a, P, E> {
+    pub fn position(&self) -> P {
+        match self {
+            Entry::Occupied(entry) => entry.position,
+            Entry::Vacant(entry) => entry.position,
+        }
+    }
+
+    /// The key of the first point at the position, which is inserted with `element` if there is
+    /// none.
+    pub fn or_insert(self, element: E) -> PointKey {
+        self.or_insert_with(|| element)
+    }
+
+    /// The key of the first point at the position, which is inserted with the element returned by
+    /// `create` if there is none.
+    pub fn or_insert_with(self, create: impl FnOnce() -> E) -> PointKey {
+        match self {
+            Entry::Occupied(entry) => entry.key,
+            Entry::Vacant(entry) => entry.insert(create()),
+        }
+    }
+}
+
+impl<'a, P: Position, E: Element> OccupiedEntry<'a, P, E> {
+    /// The key of the first point at the position.
+    pub fn key(&self) -> PointKey {
+        self.key
+    }
+
+    pub fn position(&self) -> P {
+        self.position
+    }
+
+    pub fn get(&self) -> &E {
+        &self.list.elements[self.key]
+    }
+
+    pub fn get_mut(&mut self) -> &mut E {
+        &mut self.list.elements[self.key]
+    }
+
+    pub fn into_mut(self) -> &'a mut E {
+        &mut self.list.elements[self.key]
+    }
+
+    /// Removes the first point at the position, returning its element.
+    pub fn remove(self) -> E {
+        self.list.remove_element(self.key).unwrap()
+    }
+}
+
+impl<'a, P: Position, E: Element> VacantEntry<'a, P, E> {
+    pub fn position(&self) -> P {
+        self.position
+    }
+
+    /// Inserts a point holding `element` at the position, without searching for it again.
+    pub fn insert(self, element: E) -> PointKey {
+        let Self { list, position, previous } = self;
+        if list.is_empty() {
+            return list.add_element(element, position);
+        }
+
+        let point_key = list.index.new_point();
+        list.index.insert_key_after(point_key, previous, position);
+        list.elements.insert(point_key, element);
+        point_key
+    }
+}
+
+impl<P: Position, E: Element> PointList<P, E> {
+    /// The entry at `position`, for inspecting, changing, removing or inserting the point there in
+    /// a single search.
+    pub fn entry(&mut self, position: P) -> Entry<'_, P, E> {
+        let previous = self.index.find_last(|other_position| other_position < position);
+        match self.index.next_after(previous) {
+            Some((index_in_frame, found_position)) if found_position == position => {
+                let key = self.index.key_of(index_in_frame);
+                Entry::Occupied(OccupiedEntry { list: self, key, position })
+            }
+            _ => Entry::Vacant(VacantEntry { list: self, position, previous }),
+        }
+    }
+}
@@ -0,0 +1,336 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use num_traits::zero;
+use crate::{PointIndex, PointList, PointKey, BaseFrame, EitherFrame, Element, Embedding, Frame, FrameKey, IndexInFrame, Position, FRAME_CAPACITY};
+
+/// The reason why a point could not be inserted relative to an existing point, or a list could not
+/// be appended.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum InsertError {
+    /// The given key does not belong to a point in the list.
+    UnknownKey,
+    /// The new point would lie beyond the neighbouring point.
+    CrossesNeighbour,
+    /// The given distance is negative.
+    NegativeDistance,
+}
+
+impl Display for InsertError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InsertError::UnknownKey =>
+                f.write_str("the key does not belong to a point in the list"),
+            InsertError::CrossesNeighbour =>
+                f.write_str("the new point would lie beyond the neighbouring point"),
+            InsertError::NegativeDistance =>
+                f.write_str("the distance is negative"),
+        }
+    }
+}
+
+impl Error for InsertError {}
+
+impl<P: Position> PointIndex<P> {
+    /// Moves the keys or frames from `at` onwards out of the frame at `frame_key` into a new frame,
+    /// which is inserted right after it into its meta frame (or into a new root, if the frame was
+    /// the root). Returns the key of the new frame.
+    fn split_frame(&mut self, frame_key: FrameKey, at: usize) -> FrameKey {
+        let embedding = self.frames[frame_key].embedding();
+
+        // the new frame is embedded below
+        let (split_off, distance, count): (EitherFrame<P>, P, usize) = match &mut self.frames[frame_key] {
+            EitherFrame::Meta(frame) => {
+                let count = frame.counts.position(at);
+                let (split_off, distance) = frame.split_off(at, Embedding::InList);
+                (split_off.into(), distance, count)
+            }
+            EitherFrame::Base(frame) => {
+                let (split_off, distance) = frame.split_off(at, Embedding::InList);
+                (split_off.into(), distance, at)
+            }
+        };
+        let split_off_key = self.frames.insert(split_off);
+        self.reindex(split_off_key, 0);
+        if self.last_base_frame == Some(frame_key) {
+            self.last_base_frame = Some(split_off_key);
+        }
+
+        match embedding {
+            Embedding::InMetaFrame(IndexInFrame { frame, index }) => {
+                self.insert_frame(frame, index + 1, split_off_key, distance, count);
+            }
+            Embedding::InList => {
+                // put both frames into a new root
+                let new_root_key = self.raise_root();
+                self.insert_frame(new_root_key, 1, split_off_key, distance, count);
+            }
+        }
+
+        split_off_key
+    }
+
+    /// Splits the frame at `frame_key` if it is full. Returns the frame and the index at which a
+    /// key or frame that was supposed to be inserted at `index` into the original frame must be
+    /// inserted instead.
+    fn make_room(&mut self, frame_key: FrameKey, index: usize) -> (FrameKey, usize) {
+        if self.frames[frame_key].len() < FRAME_CAPACITY {
+            return (frame_key, index);
+        }
+
+        // split such that both halves are equally full after the insertion
+        let at = if index <= FRAME_CAPACITY / 2 {
+            FRAME_CAPACITY / 2
+        } else {
+            FRAME_CAPACITY / 2 + 1
+        };
+        let split_off_key = self.split_frame(frame_key, at);
+        if index <= at {
+            (frame_key, index)
+        } else {
+            (split_off_key, index - at)
+        }
+    }
+
+    /// `index` must be positive. `count_of_previous` is the number of points in the frame at
+    /// `index - 1`.
+    fn insert_frame(&mut self, meta_frame_key: FrameKey, index: usize, key: FrameKey, distance_from_previous: P, count_of_previous: usize) {
+        let (meta_frame_key, index) = self.make_room(meta_frame_key, index);
+        self.frames[meta_frame_key].unwrap_meta_mut()
+            .insert_frame(index, key, distance_from_previous, count_of_previous);
+        self.reindex(meta_frame_key, index);
+    }
+
+    /// `index` must be positive.
+    fn insert_key(&mut self, base_frame_key: FrameKey, index: usize, key: PointKey, distance_from_previous: P) {
+        let (base_frame_key, index) = self.make_room(base_frame_key, index);
+        self.frames[base_frame_key].unwrap_base_mut().insert_key(index, key, distance_from_previous);
+        self.reindex(base_frame_key, index);
+        self.increase_counts(base_frame_key, 1);
+    }
+
+    /// Inserts `key` before the first point, `distance_to_first` before it.
+    ///
+    /// Mirroring [`Self::add_point`], the key is added to the first base frame if it is not full,
+    /// and otherwise to a new frame in front of the lowest frame on the left spine that is not
+    /// full.
+    fn insert_first_key(&mut self, key: PointKey, distance_to_first: P) {
+        let mut frame_key = self.root.unwrap();
+        while let EitherFrame::Meta(frame) = &self.frames[frame_key] {
+            frame_key = frame.first_frame();
+        }
+        while self.frames[frame_key].len() == FRAME_CAPACITY {
+            frame_key = match self.frames[frame_key].embedding() {
+                Embedding::InMetaFrame(IndexInFrame { frame, .. }) => frame,
+                Embedding::InList => self.raise_root(),
+            };
+        }
+
+        match &mut self.frames[frame_key] {
+            EitherFrame::Meta(frame) => {
+                let level = frame.level;
+                let (base_frame, index) = BaseFrame::new_with_key(key, Embedding::InList);
+                let base_frame_key = self.frames.insert(base_frame.into());
+                self.point_indices[key] = IndexInFrame::new(base_frame_key, index);
+
+                // wrap base_frame in MetaFrames until it fits into the frame at frame_key
+                let current_frame = self.wrap_frame(base_frame_key, level - 1);
+                self.frames[frame_key].unwrap_meta_mut().insert_first_frame(current_frame, distance_to_first, 1);
+            }
+            EitherFrame::Base(frame) => {
+                frame.insert_first_key(key, distance_to_first);
+            }
+        }
+        self.reindex(frame_key, 0);
+        self.increase_counts(frame_key, 1);
+
+        self.first_point_moved_earlier(frame_key, distance_to_first);
+        self.start -= distance_to_first;
+    }
+
+    /// Registers a new point. Its key still has to be inserted into a base frame.
+    pub(crate) fn new_point(&mut self) -> PointKey {
+        self.len += 1;
+        // the index in frame is updated when the key is inserted into its base frame
+        self.point_indices.insert(IndexInFrame::new(self.root.unwrap(), 0))
+    }
+
+    /// Inserts `key` at `position`, after any points at the same position. The list may not be
+    /// empty.
+    fn insert_key_at(&mut self, key: PointKey, position: P) {
+        let previous = self.find_last(|previous_position| previous_position <= position);
+        self.insert_key_after(key, previous, position);
+    }
+
+    /// Inserts `key` at `position`, right after `previous`, which is the index in frame and the
+    /// position of a point at or before `position`, or `None` if the key becomes the first one.
+    /// The list may not be empty.
+    pub(crate) fn insert_key_after(&mut self, key: PointKey, previous: Option<(IndexInFrame, P)>, position: P) {
+        match previous {
+            Some((IndexInFrame { frame, index }, previous_position)) => {
+                self.insert_key(frame, index + 1, key, position - previous_position);
+                self.end = self.end.max(position);
+            }
+            None => {
+                self.insert_first_key(key, self.start - position);
+            }
+        }
+    }
+
+    /// Inserts a point at `position`, which may lie before, between or after the existing points.
+    /// If there already are points at `position`, the new point is placed after them.
+    pub fn insert_point(&mut self, position: P) -> PointKey {
+        if self.is_empty() {
+            return self.add_point(position);
+        }
+
+        let point_key = self.new_point();
+        self.insert_key_at(point_key, position);
+        point_key
+    }
+
+    /// Moves the point at `key` to `new_position`, keeping its key. Returns the
+    /// previous position of the point, or `None` if there is no point at `key`.
+    ///
+    /// If other points lie at `new_position`, the point is placed after them, unless it already
+    /// lies there.
+    pub fn relocate(&mut self, key: PointKey, new_position: P) -> Option<P> {
+        let position = self.position(key)?;
+        if new_position == position {
+            return Some(position);
+        }
+
+        let index_in_frame = self.point_indices[key];
+        self.detach(index_in_frame);
+        self.len += 1;
+
+        if self.root.is_some() {
+            self.insert_key_at(key, new_position);
+        } else {
+            // the point was the only one
+            let (base_frame, index) = BaseFrame::new_with_key(key, Embedding::InList);
+            let base_frame_key = self.frames.insert(base_frame.into());
+            self.point_indices[key] = IndexInFrame::new(base_frame_key, index);
+            self.root = Some(base_frame_key);
+            self.last_base_frame = Some(base_frame_key);
+            self.start = new_position;
+            self.end = new_position;
+        }
+
+        Some(position)
+    }
+
+    /// Inserts a point `distance_to_first` before the first point. If `distance_to_first` is zero,
+    /// the new point is placed before the points at the start. If the index is empty, there is no
+    /// first point to measure from, and the new point is placed at [`Self::start`].
+    ///
+    /// Fails with [`InsertError::NegativeDistance`] if `distance_to_first` is negative.
+    pub fn push_front_point(&mut self, distance_to_first: P) -> Result<PointKey, InsertError> {
+        if distance_to_first < zero() {
+            return Err(InsertError::NegativeDistance);
+        }
+
+        if self.is_empty() {
+            return Ok(self.add_point(self.start));
+        }
+
+        let point_key = self.new_point();
+        self.insert_first_key(point_key, distance_to_first);
+        Ok(point_key)
+    }
+
+    /// Inserts a point `distance` after the point at `key`, directly behind it in positional order.
+    ///
+    /// Fails if `distance` is negative or if the new point would lie after the next point, if any.
+    pub fn insert_point_after(&mut self, key: PointKey, distance: P) -> Result<PointKey, InsertError> {
+        if distance < zero() {
+            return Err(InsertError::NegativeDistance);
+        }
+
+        let index_in_frame = *self.point_indices.get(key).ok_or(InsertError::UnknownKey)?;
+        match self.next_of(index_in_frame) {
+            Some((_, distance_to_next)) => {
+                if distance > distance_to_next {
+                    return Err(InsertError::CrossesNeighbour);
+                }
+            }
+            None => self.end += distance,
+        }
+
+        let point_key = self.new_point();
+        self.insert_key(index_in_frame.frame, index_in_frame.index + 1, point_key, distance);
+        Ok(point_key)
+    }
+
+    /// Inserts a point `distance` before the point at `key`, directly in front of it in positional
+    /// order.
+    ///
+    /// Fails if `distance` is negative or if the new point would lie before the previous point, if
+    /// any.
+    pub fn insert_point_before(&mut self, key: PointKey, distance: P) -> Result<PointKey, InsertError> {
+        if distance < zero() {
+            return Err(InsertError::NegativeDistance);
+        }
+
+        let index_in_frame = *self.point_indices.get(key).ok_or(InsertError::UnknownKey)?;
+        match self.previous_of(index_in_frame) {
+            Some((previous, distance_to_previous)) => {
+                if distance > distance_to_previous {
+                    return Err(InsertError::CrossesNeighbour);
+                }
+                let point_key = self.new_point();
+                self.insert_key(previous.frame, previous.index + 1, point_key, distance_to_previous - distance);
+                Ok(point_key)
+            }
+            None => {
+                let point_key = self.new_point();
+                self.insert_first_key(point_key, distance);
+                Ok(point_key)
+            }
+        }
+    }
+}
+
+impl<P: Position, E: Element> PointList<P, E> {
+    /// Inserts `element` at `position`, which may lie before, between or after the existing points.
+    /// If there already are points at `position`, the new point is placed after them.
+    pub fn insert_element(&mut self, element: E, position: P) -> PointKey {
+        let point_key = self.index.insert_point(position);
+        self.elements.insert(point_key, element);
+        point_key
+    }
+
+    /// Moves the point at `key` to `new_position`, keeping its key and element, see
+    /// [`PointIndex::relocate`].
+    pub fn relocate(&mut self, key: PointKey, new_position: P) -> Option<P> {
+        self.index.relocate(key, new_position)
+    }
+
+    /// Inserts `element` `distance_to_first` before the first point, or at [`PointIndex::start`] if
+    /// the list is empty, see [`PointIndex::push_front_point`].
+    pub fn push_front(&mut self, element: E, distance_to_first: P) -> Result<PointKey, InsertError> {
+        let point_key = self.index.push_front_point(distance_to_first)?;
+        self.elements.insert(point_key, element);
+        Ok(point_key)
+    }
+
+    /// Inserts `element` `distance` after the point at `key`, directly behind it in positional
+    /// order.
+    ///
+    /// Fails if `distance` is negative or if the new point would lie after the next point, if any.
+    pub fn insert_after(&mut self, key: PointKey, distance: P, element: E) -> Result<PointKey, InsertError> {
+        let point_key = self.index.insert_point_after(key, distance)?;
+        self.elements.insert(point_key, element);
+        Ok(point_key)
+    }
+
+    /// Inserts `element` `distance` before the point at `key`, directly in front of it in
+    /// positional order.
+    ///
+    /// Fails if `distance` is negative or if the new point would lie before the previous point, if
+    /// any.
+    pub fn insert_before(&mut self, key: PointKey, distance: P, element: E) -> Result<PointKey, InsertError> {
+        let point_key = self.index.insert_point_before(key, distance)?;
+        self.elements.insert(point_key, element);
+        Ok(point_key)
+    }
+}
@@ -0,0 +1,158 @@
+use std::ops::{Bound, RangeBounds};
+use slotmap::SecondaryMap;
+use crate::{Element, IndexInFrame, PointIndex, PointKey, PointList, Position};
+
+/// Iterates over the points between two points (both inclusive) in positional order, keeping
+/// track of the positions of both ends as it goes.
+pub(crate) struct Iter<'a, P: Position> {
+    index: &'a PointIndex<P>,
+    /// The next points to be yielded from the front and from the back, `None` when exhausted.
+    ends: Option<((IndexInFrame, P), (IndexInFrame, P))>,
+}
+
+impl<'a, P: Position> Iter<'a, P> {
+    fn item(&self, (index_in_frame, position): (IndexInFrame, P)) -> (PointKey, P) {
+        (self.index.key_of(index_in_frame), position)
+    }
+}
+
+impl<'a, P: Position> Iterator for Iter<'a, P> {
+    type Item = (PointKey, P);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (front, back) = self.ends?;
+        if front.0 == back.0 {
+            self.ends = None;
+        } else {
+            let (next, distance) = self.index.next_of(front.0).unwrap();
+            self.ends = Some(((next, front.1 + distance), back));
+        }
+        Some(self.item(front))
+    }
+}
+
+impl<'a, P: Position> DoubleEndedIterator for Iter<'a, P> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let (front, back) = self.ends?;
+        if front.0 == back.0 {
+            self.ends = None;
+        } else {
+            let (previous, distance) = self.index.previous_of(back.0).unwrap();
+            self.ends = Some((front, (previous, back.1 - distance)));
+        }
+        Some(self.item(back))
+    }
+}
+
+/// Iterates over the points of a [`PointList`] like [`Iter`], yielding their elements mutably.
+pub(crate) struct IterMut<'a, P: Position, E: Element> {
+    points: Iter<'a, P>,
+    /// The elements that have not been yielded yet, each of which is taken out when it is.
+    elements: SecondaryMap<PointKey, &'a mut E>,
+}
+
+impl<'a, P: Position, E: Element> IterMut<'a, P, E> {
+    fn item(&mut self, (key, position): (PointKey, P)) -> (PointKey, P, &'a mut E) {
+        (key, position, self.elements.remove(key).unwrap())
+    }
+}
+
+impl<'a, P: Position, E: Element> Iterator for IterMut<'a, P, E> {
+    type Item = (PointKey, P, &'a mut E);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let point = self.points.next()?;
+        Some(self.item(point))
+    }
+}
+
+impl<'a, P: Position, E: Element> DoubleEndedIterator for IterMut<'a, P, E> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let point = self.points.next_back()?;
+        Some(self.item(point))
+    }
+}
+
+impl<P: Position> PointIndex<P> {
+    /// The points yielded by [`Self::iter`].
+    fn points(&self) -> Iter<'_, P> {
+        let ends = self.first_key().zip(self.last_key()).map(|(first_key, last_key)| (
+            (self.point_indices[first_key], self.start),
+            (self.point_indices[last_key], self.end),
+        ));
+        Iter { index: self, ends }
+    }
+
+    /// Iterates over the keys and positions of all points, in positional order.
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = (PointKey, P)> + '_ {
+        self.points()
+    }
+
+    /// Iterates over the keys of all points, in positional order.
+    pub fn keys(&self) -> impl DoubleEndedIterator<Item = PointKey> + '_ {
+        self.iter().map(|(key, _)| key)
+    }
+
+    /// Iterates over the positions of all points, in positional order.
+    pub fn positions(&self) -> impl DoubleEndedIterator<Item = P> + '_ {
+        self.iter().map(|(_, position)| position)
+    }
+
+    /// Iterates over the keys and positions of the points whose positions lie in `range`, in
+    /// positional order.
+    pub fn range(&self, range: impl RangeBounds<P>) -> impl DoubleEndedIterator<Item = (PointKey, P)> + '_ {
+        let front = match range.start_bound() {
+            Bound::Included(&start) =>
+                self.next_after(self.find_last(|position| position < start)),
+            Bound::Excluded(&start) =>
+                self.next_after(self.find_last(|position| position <= start)),
+            Bound::Unbounded =>
+                self.next_after(None),
+        };
+        let back = match range.end_bound() {
+            Bound::Included(&end) => self.find_last(|position| position <= end),
+            Bound::Excluded(&end) => self.find_last(|position| position < end),
+            Bound::Unbounded => self.find_last(|_| true),
+        };
+        let ends = front.zip(back).filter(|(front, back)| front.1 <= back.1);
+        Iter { index: self, ends }
+    }
+}
+
+impl<P: Position, E: Element> PointList<P, E> {
+    /// Iterates over the keys, positions and elements of all points, in positional order.
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = (PointKey, P, &E)> {
+        self.index.iter().map(|(key, position)| (key, position, &self.elements[key]))
+    }
+
+    /// Iterates over the keys of all points, in positional order.
+    pub fn keys(&self) -> impl DoubleEndedIterator<Item = PointKey> + '_ {
+        self.index.keys()
+    }
+
+    /// Iterates over the positions of all points, in positional order.
+    pub fn positions(&self) -> impl DoubleEndedIterator<Item = P> + '_ {
+        self.index.positions()
+    }
+
+    /// Iterates over the elements of all points, in positional order.
+    pub fn elements(&self) -> impl DoubleEndedIterator<Item = &E> {
+        self.iter().map(|(_, _, element)| element)
+    }
+
+    /// Iterates over the keys, positions and mutable elements of all points, in positional order.
+    pub fn iter_mut(&mut self) -> impl DoubleEndedIterator<Item = (PointKey, P, &mut E)> {
+        IterMut { points: self.index.points(), elements: self.elements.iter_mut().collect() }
+    }
+
+    /// Iterates over the mutable elements of all points, in positional order.
+    pub fn elements_mut(&mut self) -> impl DoubleEndedIterator<Item = &mut E> {
+        self.iter_mut().map(|(_, _, element)| element)
+    }
+
+    /// Iterates over the keys, positions and elements of the points whose positions lie in
+    /// `range`, in positional order.
+    pub fn range(&self, range: impl RangeBounds<P>) -> impl DoubleEndedIterator<Item = (PointKey, P, &E)> {
+        self.index.range(range).map(|(key, position)| (key, position, &self.elements[key]))
+    }
+}
@@ -0,0 +1,178 @@
+use crate::{EitherFrame, Element, Embedding, Frame, IndexInFrame, PointIndex, PointKey, PointList, Position};
+
+impl<P: Position> PointIndex<P> {
+    /// The key of the point at exactly `position`, if there is one. If there are several, the key
+    /// of the first one is returned.
+    pub fn key_at(&self, position: P) -> Option<PointKey> {
+        let before = self.find_last(|other_position| other_position < position);
+        let (index_in_frame, found_position) = self.next_after(before)?;
+        (found_position == position).then(|| self.key_of(index_in_frame))
+    }
+
+    /// The key and position of the last point at or before `position`, if there is one.
+    pub fn key_at_or_before(&self, position: P) -> Option<(PointKey, P)> {
+        let found = self.find_last(|other_position| other_position <= position);
+        self.with_key(found)
+    }
+
+    /// The key and position of the last point before `position`, if there is one.
+    pub fn key_before(&self, position: P) -> Option<(PointKey, P)> {
+        let found = self.find_last(|other_position| other_position < position);
+        self.with_key(found)
+    }
+
+    /// The key and position of the first point at or after `position`, if there is one.
+    pub fn key_at_or_after(&self, position: P) -> Option<(PointKey, P)> {
+        let before = self.find_last(|other_position| other_position < position);
+        self.with_key(self.next_after(before))
+    }
+
+    /// The key and position of the first point after `position`, if there is one.
+    pub fn key_after(&self, position: P) -> Option<(PointKey, P)> {
+        let at_or_before = self.find_last(|other_position| other_position <= position);
+        self.with_key(self.next_after(at_or_before))
+    }
+
+    /// The key and position of the point closest to `position`, if it lies within `tolerance` of
+    /// it. If the closest points before and after `position` are equally close, the one before is
+    /// returned.
+    ///
+    /// Useful for positions that are subject to rounding errors, such as
+    /// [`FloatPosition`](crate::FloatPosition)s.
+    pub fn key_within(&self, position: P, tolerance: P) -> Option<(PointKey, P)> {
+        let before = self.key_before(position)
+            .filter(|&(_, before_position)| position - before_position <= tolerance);
+        let after = self.key_at_or_after(position)
+            .filter(|&(_, after_position)| after_position - position <= tolerance);
+        match (before, after) {
+            (Some(before), Some(after)) =>
+                Some(if after.1 - position < position - before.1 { after } else { before }),
+            (before, after) => before.or(after),
+        }
+    }
+
+    /// The key of the point after the point at `key`, if both exist.
+    pub fn next_key(&self, key: PointKey) -> Option<PointKey> {
+        let (next, _) = self.next_of(*self.point_indices.get(key)?)?;
+        Some(self.key_of(next))
+    }
+
+    /// The key of the point before the point at `key`, if both exist.
+    pub fn prev_key(&self, key: PointKey) -> Option<PointKey> {
+        let (previous, _) = self.previous_of(*self.point_indices.get(key)?)?;
+        Some(self.key_of(previous))
+    }
+
+    /// The distance between the point at `key` and the point after it, if both exist.
+    pub fn distance_to_next(&self, key: PointKey) -> Option<P> {
+        let (_, distance) = self.next_of(*self.point_indices.get(key)?)?;
+        Some(distance)
+    }
+
+    /// The distance between the point at `key` and the point before it, if both exist.
+    pub fn distance_to_prev(&self, key: PointKey) -> Option<P> {
+        let (_, distance) = self.previous_of(*self.point_indices.get(key)?)?;
+        Some(distance)
+    }
+
+    /// The key of the point at `index` in positional order, if there is one.
+    pub fn nth(&self, index: usize) -> Option<PointKey> {
+        if index >= self.len {
+            return None;
+        }
+
+        let mut frame_key = self.root?;
+        let mut remaining = index;
+        loop {
+            match &self.frames[frame_key] {
+                EitherFrame::Meta(frame) => {
+                    let (frame_index, count) = frame.counts
+                        .search(frame.len() - 1, |count| count <= remaining);
+                    remaining -= count;
+                    frame_key = frame.frames[frame_index];
+                }
+                EitherFrame::Base(frame) => break Some(frame.keys[remaining]),
+            }
+        }
+    }
+
+    /// The index of the point at `key` in positional order, if there is such a point.
+    pub fn index_of(&self, key: PointKey) -> Option<usize> {
+        let index_in_frame = self.point_indices.get(key)?;
+        let mut index = index_in_frame.index;
+        let mut embedding = self.frames[index_in_frame.frame].embedding();
+        while let Embedding::InMetaFrame(IndexInFrame { frame, index: frame_index }) = embedding {
+            let meta_frame = self.frames[frame].unwrap_meta();
+            index += meta_frame.counts.position(frame_index);
+            embedding = meta_frame.embedding;
+        }
+        Some(index)
+    }
+
+    fn with_key(&self, found: Option<(IndexInFrame, P)>) -> Option<(PointKey, P)> {
+        found.map(|(index_in_frame, position)| (self.key_of(index_in_frame), position))
+    }
+}
+
+impl<P: Position, E: Element> PointList<P, E> {
+    /// The key of the point at exactly `position`, if there is one, see [`PointIndex::key_at`].
+    pub fn key_at(&self, position: P) -> Option<PointKey> {
+        self.index.key_at(position)
+    }
+
+    /// The key and position of the last point at or before `position`, if there is one.
+    pub fn key_at_or_before(&self, position: P) -> Option<(PointKey, P)> {
+        self.index.key_at_or_before(position)
+    }
+
+    /// The key and position of the last point before `position`, if there is one.
+    pub fn key_before(&self, position: P) -> Option<(PointKey, P)> {
+        self.index.key_before(position)
+    }
+
+    /// The key and position of the first point at or after `position`, if there is one.
+    pub fn key_at_or_after(&self, position: P) -> Option<(PointKey, P)> {
+        self.index.key_at_or_after(position)
+    }
+
+    /// The key and position of the first point after `position`, if there is one.
+    pub fn key_after(&self, position: P) -> Option<(PointKey, P)> {
+        self.index.key_after(position)
+    }
+
+    /// The key and position of the point closest to `position`, if it lies within `tolerance` of
+    /// it, see [`PointIndex::key_within`].
+    pub fn key_within(&self, position: P, tolerance: P) -> Option<(PointKey, P)> {
+        self.index.key_within(position, tolerance)
+    }
+
+    /// The key of the point after the point at `key`, if both exist.
+    pub fn next_key(&self, key: PointKey) -> Option<PointKey> {
+        self.index.next_key(key)
+    }
+
+    /// The key of the point before the point at `key`, if both exist.
+    pub fn prev_key(&self, key: PointKey) -> Option<PointKey> {
+        self.index.prev_key(key)
+    }
+
+    /// The distance between the point at `key` and the point after it, if both exist.
+    pub fn distance_to_next(&self, key: PointKey) -> Option<P> {
+        self.index.distance_to_next(key)
+    }
+
+    /// The distance between the point at `key` and the point before it, if both exist.
+    pub fn distance_to_prev(&self, key: PointKey) -> Option<P> {
+        self.index.distance_to_prev(key)
+    }
+
+    /// The key of the point at `index` in positional order, if there is one.
+    pub fn nth(&self, index: usize) -> Option<PointKey> {
+        self.index.nth(index)
+    }
+
+    /// The index of the point at `key` in positional order, if there is such a point.
+    pub fn index_of(&self, key: PointKey) -> Option<usize> {
+        self.index.index_of(key)
+    }
+}
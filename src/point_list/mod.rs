@@ -1,10 +1,19 @@
-use std::fmt::Debug;
 use slotmap::{new_key_type, SecondaryMap, SlotMap};
 use num_traits::zero;
-use crate::{EitherFrame, Element, Embedding, Frame, FrameKey, IndexInFrame, Position};
+use crate::{EitherFrame, Element, Embedding, Frame, FrameKey, IndexInFrame, MetaFrame, Position};
 
 pub mod add_element;
+pub mod insert_element;
+pub mod lookup;
+pub mod iter;
+pub mod shift;
 pub mod remove_element;
+pub mod split;
+pub mod append;
+pub mod build;
+pub mod drain;
+pub mod entry;
+pub mod cursor;
 pub mod debug;
 
 #[cfg(test)]
@@ -12,19 +21,52 @@ mod tests;
 
 new_key_type! { pub struct PointKey; }
 
-// TODO remove elements list from PointList? (such that elements are stored outside the PointList)
+/// The direction in which points are moved.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+enum Direction {
+    /// Towards greater positions.
+    Later,
+    /// Towards smaller positions.
+    Earlier,
+}
+
+/// Clamps the distance between two neighbouring points at zero. With
+/// [`FloatPosition`](crate::FloatPosition)s, a distance that is computed from the sums stored in
+/// the frames may come out slightly negative due to rounding, but never for exact positions.
+fn clamp_distance<P: Position>(distance: P) -> P {
+    distance.max(zero())
+}
+
+/// The keys and positions of a set of points, without any elements attached to them.
+///
+/// Useful when the elements are stored elsewhere, keyed by [`PointKey`]. A [`PointList`] is a
+/// `PointIndex` together with the elements of its points, see [`PointList::index`].
 #[derive(Clone)]
-pub struct PointList<P: Position, E: Element> {
+pub struct PointIndex<P: Position> {
     frames: SlotMap<FrameKey, EitherFrame<P>>,
-    // TODO put root, start and end all in the same Option (that is None for empty PointLists)?
+    // TODO put root, start and end all in the same Option (that is None for empty PointIndices)?
     root: Option<FrameKey>,
+    /// The base frame holding the last key, such that the right spine can be climbed from it
+    /// without descending from the root.
+    last_base_frame: Option<FrameKey>,
     start: P,
     end: P,
     len: usize,
     point_indices: SlotMap<PointKey, IndexInFrame>,
+}
+
+#[derive(Clone)]
+pub struct PointList<P: Position, E: Element> {
+    index: PointIndex<P>,
     elements: SecondaryMap<PointKey, E>,
 }
 
+impl<P: Position> Default for PointIndex<P> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl<P: Position, E: Element> Default for PointList<P, E> {
     fn default() -> Self {
         Self::new()
@@ -32,19 +74,86 @@ impl<P: Position, E: Element> Default for PointList<P, E> {
 }
 
 impl<P: Position, E: Element> PointList<P, E> {
+    pub fn new() -> Self {
+        Self {
+            index: PointIndex::new(),
+            elements: SecondaryMap::new(),
+        }
+    }
+
+    /// The keys and positions of the points, without their elements.
+    pub fn index(&self) -> &PointIndex<P> {
+        &self.index
+    }
+
+    /// The number of points stored in this list.
+    ///
+    /// Not to be confused with [`Self::length`].
+    pub fn len(&self) -> usize {
+        self.index.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.index.is_empty()
+    }
+
+    pub fn start(&self) -> P {
+        self.index.start()
+    }
+
+    pub fn end(&self) -> P {
+        self.index.end()
+    }
+
+    /// The distance between the first and last points.
+    /// Zero for empty lists.
+    ///
+    /// Not to be confused with [`Self::len`].
+    pub fn length(&self) -> P {
+        self.index.length()
+    }
+
+    pub fn first_key(&self) -> Option<PointKey> {
+        self.index.first_key()
+    }
+
+    pub fn last_key(&self) -> Option<PointKey> {
+        self.index.last_key()
+    }
+
+    pub fn position(&self, key: PointKey) -> Option<P> {
+        self.index.position(key)
+    }
+
+    pub fn element(&self, key: PointKey) -> Option<&E> {
+        self.elements.get(key)
+    }
+
+    pub fn element_mut(&mut self, key: PointKey) -> Option<&mut E> {
+        self.elements.get_mut(key)
+    }
+
+    /// Mutable references to the elements of the points at `keys`, or `None` if any key does not
+    /// belong to a point in the list or occurs more than once.
+    pub fn get_disjoint_mut<const N: usize>(&mut self, keys: [PointKey; N]) -> Option<[&mut E; N]> {
+        self.elements.get_disjoint_mut(keys)
+    }
+}
+
+impl<P: Position> PointIndex<P> {
     pub fn new() -> Self {
         Self {
             frames: SlotMap::with_key(),
             root: None,
+            last_base_frame: None,
             start: zero(),
             end: zero(),
             len: 0,
             point_indices: SlotMap::with_key(),
-            elements: SecondaryMap::new(),
         }
     }
 
-    /// The number of elements stored in this list.
+    /// The number of points stored in this index.
     ///
     /// Not to be confused with [`Self::length`].
     pub fn len(&self) -> usize {
@@ -63,22 +172,22 @@ impl<P: Position, E: Element> PointList<P, E> {
         self.end
     }
 
-    /// The distance between this list's first and last elements.
-    /// Zero for empty lists.
+    /// The distance between the first and last points.
+    /// Zero for empty indices.
     ///
     /// Not to be confused with [`Self::len`].
     pub fn length(&self) -> P {
         self.end - self.start
     }
 
-    // TODO store first_key and last_key in fields for more performance?
+    // TODO store first_key in a field for more performance?
 
     pub fn first_key(&self) -> Option<PointKey> {
         self.root.map(|root| self.first_key_of(root))
     }
 
     pub fn last_key(&self) -> Option<PointKey> {
-        self.root.map(|root| self.last_key_of(root))
+        self.last_base_frame.map(|frame_key| self.frames[frame_key].unwrap_base().last_key())
     }
 
     fn first_key_of(&self, frame_key: FrameKey) -> PointKey {
@@ -88,28 +197,254 @@ impl<P: Position, E: Element> PointList<P, E> {
         }
     }
 
-    fn last_key_of(&self, frame_key: FrameKey) -> PointKey {
+    /// Updates [`Self::last_base_frame`] after the structure of the list was changed.
+    fn cache_last_base_frame(&mut self) {
+        self.last_base_frame = self.root.map(|mut frame_key| {
+            while let EitherFrame::Meta(frame) = &self.frames[frame_key] {
+                frame_key = frame.last_frame();
+            }
+            frame_key
+        });
+    }
+
+    /// Puts the root into a new root one level above it and returns the key of the new root.
+    fn raise_root(&mut self) -> FrameKey {
+        let root_key = self.root.unwrap();
+        let level = self.frames[root_key].level() + 1;
+        let (new_root, index) =
+            MetaFrame::new_with_frame(root_key, level, Embedding::InList);
+        let new_root_key = self.frames.insert(new_root.into());
+        self.frames[root_key].embed(Embedding::InMetaFrame(IndexInFrame::new(new_root_key, index)));
+        self.root = Some(new_root_key);
+        new_root_key
+    }
+
+    /// Wraps the frame at `frame_key` in new MetaFrames until the outermost one has the given
+    /// level, returning the key of the outermost frame.
+    fn wrap_frame(&mut self, mut frame_key: FrameKey, level: usize) -> FrameKey {
+        for level in self.frames[frame_key].level() + 1..=level {
+            let (meta_frame, index) = MetaFrame::new_with_frame(frame_key, level, Embedding::InList);
+            let meta_frame_key = self.frames.insert(meta_frame.into());
+            self.frames[frame_key].embed(Embedding::InMetaFrame(IndexInFrame::new(meta_frame_key, index)));
+            frame_key = meta_frame_key;
+        }
+        frame_key
+    }
+
+    /// Updates the stored indices of the keys or frames in the frame at `frame_key`, starting at
+    /// `from`.
+    fn reindex(&mut self, frame_key: FrameKey, from: usize) {
         match &self.frames[frame_key] {
-            EitherFrame::Meta(frame) => self.last_key_of(frame.last_frame()),
-            EitherFrame::Base(frame) => frame.last_key()
+            EitherFrame::Meta(frame) => {
+                for (index, child) in frame.frames.clone().into_iter().enumerate().skip(from) {
+                    self.frames[child].embed(Embedding::InMetaFrame(IndexInFrame::new(frame_key, index)));
+                }
+            }
+            EitherFrame::Base(frame) => {
+                for (index, &point_key) in frame.keys.iter().enumerate().skip(from) {
+                    self.point_indices[point_key] = IndexInFrame::new(frame_key, index);
+                }
+            }
         }
     }
 
-    fn length_of(&self, frame_key: FrameKey) -> P {
+    fn key_of(&self, IndexInFrame { frame, index }: IndexInFrame) -> PointKey {
+        self.frames[frame].unwrap_base().keys[index]
+    }
+
+    /// The index in frame and the position of the last point whose position satisfies `predicate`.
+    ///
+    /// Once `predicate` fails for some position, it must fail for all greater positions.
+    fn find_last(&self, predicate: impl Fn(P) -> bool) -> Option<(IndexInFrame, P)> {
+        let mut frame_key = self.root?;
+        let mut position = self.start;
+        if !predicate(position) {
+            return None;
+        }
+        loop {
+            let frame = &self.frames[frame_key];
+            let (index, offset) = frame.distances()
+                .search(frame.len() - 1, |offset| predicate(position + offset));
+            position += offset;
+            match frame {
+                EitherFrame::Meta(frame) => frame_key = frame.frames[index],
+                EitherFrame::Base(_) => break Some((IndexInFrame::new(frame_key, index), position)),
+            }
+        }
+    }
+
+    /// The index in frame and position of the point after `found`, or of the first point if
+    /// `found` is `None`.
+    fn next_after(&self, found: Option<(IndexInFrame, P)>) -> Option<(IndexInFrame, P)> {
+        match found {
+            Some((index_in_frame, position)) => {
+                let (next, distance) = self.next_of(index_in_frame)?;
+                Some((next, position + distance))
+            }
+            None => {
+                let first_key = self.first_key()?;
+                Some((self.point_indices[first_key], self.start))
+            }
+        }
+    }
+
+    /// The index in frame and position of the point before `found`, or of the last point if
+    /// `found` is `None`.
+    fn previous_before(&self, found: Option<(IndexInFrame, P)>) -> Option<(IndexInFrame, P)> {
+        match found {
+            Some((index_in_frame, position)) => {
+                let (previous, distance) = self.previous_of(index_in_frame)?;
+                Some((previous, position - distance))
+            }
+            None => {
+                let last_key = self.last_key()?;
+                Some((self.point_indices[last_key], self.end))
+            }
+        }
+    }
+
+    /// The index in frame of the point after the one at `index_in_frame`, together with the
+    /// distance between them, if there is such a point.
+    fn next_of(&self, IndexInFrame { frame, index }: IndexInFrame) -> Option<(IndexInFrame, P)> {
+        let base_frame = &self.frames[frame];
+        if index + 1 < base_frame.len() {
+            let distance = clamp_distance(base_frame.distances().distance(index));
+            return Some((IndexInFrame::new(frame, index + 1), distance));
+        }
+
+        // the point is the last point of every frame up to the one that has a next frame
+        let mut frame_key = frame;
+        loop {
+            let Embedding::InMetaFrame(IndexInFrame { frame: meta_frame_key, index }) =
+                self.frames[frame_key].embedding() else { return None; };
+            let meta_frame = self.frames[meta_frame_key].unwrap_meta();
+            if index + 1 < meta_frame.len() {
+                let distance =
+                    clamp_distance(meta_frame.distances.distance(index) - self.length_of(frame_key));
+                let mut next_frame_key = meta_frame.frames[index + 1];
+                while let EitherFrame::Meta(frame) = &self.frames[next_frame_key] {
+                    next_frame_key = frame.first_frame();
+                }
+                return Some((IndexInFrame::new(next_frame_key, 0), distance));
+            }
+            frame_key = meta_frame_key;
+        }
+    }
+
+    /// The index in frame of the point before the one at `index_in_frame`, together with the
+    /// distance between them, if there is such a point.
+    fn previous_of(&self, IndexInFrame { frame, index }: IndexInFrame) -> Option<(IndexInFrame, P)> {
+        if index > 0 {
+            let distance = clamp_distance(self.frames[frame].distances().distance(index - 1));
+            return Some((IndexInFrame::new(frame, index - 1), distance));
+        }
+
+        // the point is the first point of every frame up to the one that has a previous frame
+        let mut frame_key = frame;
+        loop {
+            let Embedding::InMetaFrame(IndexInFrame { frame: meta_frame_key, index }) =
+                self.frames[frame_key].embedding() else { return None; };
+            let meta_frame = self.frames[meta_frame_key].unwrap_meta();
+            if index > 0 {
+                let mut previous_frame_key = meta_frame.frames[index - 1];
+                let distance = clamp_distance(
+                    meta_frame.distances.distance(index - 1) - self.length_of(previous_frame_key));
+                while let EitherFrame::Meta(frame) = &self.frames[previous_frame_key] {
+                    previous_frame_key = frame.last_frame();
+                }
+                let index = self.frames[previous_frame_key].len() - 1;
+                return Some((IndexInFrame::new(previous_frame_key, index), distance));
+            }
+            frame_key = meta_frame_key;
+        }
+    }
+
+    /// The number of points in the frame at `frame_key`.
+    fn count_of(&self, frame_key: FrameKey) -> usize {
         match &self.frames[frame_key] {
             EitherFrame::Meta(frame) =>
-                frame.distances.length() + self.length_of(frame.last_frame()),
+                frame.counts.length() + self.count_of(frame.last_frame()),
             EitherFrame::Base(frame) =>
-                frame.distances.length()
+                frame.keys.len()
         }
     }
 
-    pub fn element(&self, key: PointKey) -> Option<&E> {
-        self.elements.get(key)
+    /// Adds `change` to the number of points in the frame at `frame_key` and in all frames
+    /// containing it.
+    fn increase_counts(&mut self, frame_key: FrameKey, change: usize) {
+        let mut embedding = self.frames[frame_key].embedding();
+        while let Embedding::InMetaFrame(IndexInFrame { frame, index }) = embedding {
+            let meta_frame = self.frames[frame].unwrap_meta_mut();
+            // the number of points in the last frame is not stored
+            if index + 1 < meta_frame.len() {
+                meta_frame.counts.increase_distance(index, change);
+            }
+            embedding = meta_frame.embedding;
+        }
     }
 
-    pub fn element_mut(&mut self, key: PointKey) -> Option<&mut E> {
-        self.elements.get_mut(key)
+    /// Accounts for a point having been put before the first point of the frame at `frame_key`,
+    /// `distance` before it, in the frames containing it. The frame must be on the left spine, so
+    /// the point is the first point of all of these frames as well.
+    fn first_point_moved_earlier(&mut self, frame_key: FrameKey, distance: P) {
+        let mut embedding = self.frames[frame_key].embedding();
+        while let Embedding::InMetaFrame(IndexInFrame { frame, .. }) = embedding {
+            let meta_frame = self.frames[frame].unwrap_meta_mut();
+            if meta_frame.len() > 1 {
+                meta_frame.distances.increase_distance(0, distance);
+            }
+            embedding = meta_frame.embedding;
+        }
+    }
+
+    /// Subtracts `change` from the number of points in the frame at `frame_key` and in all frames
+    /// containing it.
+    fn decrease_counts(&mut self, frame_key: FrameKey, change: usize) {
+        let mut embedding = self.frames[frame_key].embedding();
+        while let Embedding::InMetaFrame(IndexInFrame { frame, index }) = embedding {
+            let meta_frame = self.frames[frame].unwrap_meta_mut();
+            // the number of points in the last frame is not stored
+            if index + 1 < meta_frame.len() {
+                meta_frame.counts.decrease_distance(index, change);
+            }
+            embedding = meta_frame.embedding;
+        }
+    }
+
+    /// Moves every point after the one at `index_in_frame` by `change` in the given direction.
+    /// When moving earlier, `change` may not be greater than the distance to the next point.
+    fn move_after(&mut self, mut index_in_frame: IndexInFrame, change: P, direction: Direction) {
+        let mut moved = false;
+        loop {
+            let IndexInFrame { frame, index } = index_in_frame;
+            let frame = &mut self.frames[frame];
+            if index + 1 < frame.len() {
+                match direction {
+                    Direction::Later => frame.distances_mut().increase_distance(index, change),
+                    Direction::Earlier => frame.distances_mut().decrease_distance(index, change),
+                }
+                moved = true;
+            }
+            match frame.embedding() {
+                Embedding::InMetaFrame(embedding) => index_in_frame = embedding,
+                Embedding::InList => break,
+            }
+        }
+        if moved {
+            match direction {
+                Direction::Later => self.end += change,
+                Direction::Earlier => self.end -= change,
+            }
+        }
+    }
+
+    fn length_of(&self, frame_key: FrameKey) -> P {
+        match &self.frames[frame_key] {
+            EitherFrame::Meta(frame) =>
+                frame.distances.length() + self.length_of(frame.last_frame()),
+            EitherFrame::Base(frame) =>
+                frame.distances.length()
+        }
     }
 
     pub fn position(&self, key: PointKey) -> Option<P> {
@@ -124,16 +459,4 @@ impl<P: Position, E: Element> PointList<P, E> {
         }
         Some(position)
     }
-
-    // WIP
-
-    /*fn replenish_distance(&mut self, key: PointKey, index_in_frame: IndexInFrame, distance: P) {
-        if key == self.first_key().unwrap() {
-            self.start += distance;
-        } else if index_in_frame.index == 0 {
-
-        } else {
-            index_in_frame.frame.distances.increase_distance(index_of_removed - 1, distance);
-        }
-    }*/
 }
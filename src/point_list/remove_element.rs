@@ -1,9 +1,9 @@
 use num_traits::zero;
 
-use crate::{Element, Embedding, PointKey, PointList, Position};
+use crate::{Element, Embedding, PointIndex, PointKey, PointList, Position};
 use crate::frame::{EitherFrame, Frame, FRAME_CAPACITY, FrameKey, IndexInFrame};
 
-impl<P: Position, E: Element> PointList<P, E> {
+impl<P: Position> PointIndex<P> {
     fn try_merge(&mut self, meta_frame_key: FrameKey, left_index: usize) -> Result<(), ()> {
         let right_index = left_index + 1;
 
@@ -15,63 +15,61 @@ impl<P: Position, E: Element> PointList<P, E> {
 
         let left_frame_key = meta_frame.frames[left_index];
         let right_frame_key = meta_frame.frames[right_index];
-        
-        if dbg!(self.frames[left_frame_key].len()) + dbg!(self.frames[right_frame_key].len()) > dbg!(FRAME_CAPACITY) {
+
+        let left_len = self.frames[left_frame_key].len();
+        if left_len + self.frames[right_frame_key].len() > FRAME_CAPACITY {
             return Err(());
         }
 
         let distance_between_frames = meta_frame.distances.distance(left_index) - self.frames[left_frame_key].distances().length();
 
         let right_frame = self.frames.remove(right_frame_key).unwrap();
-        let left_frame = &mut self.frames[left_frame_key];
 
-        match left_frame {
+        match &self.frames[left_frame_key] {
             EitherFrame::Meta(left_frame) => {
                 let right_frame = right_frame.unwrap_meta_owned();
 
-                // let meta_frame = self.frames[meta_frame_key].unwrap_meta();
+                // the number of points in the last frame is not stored, but will be needed now
+                let count_of_last_frame = self.count_of(left_frame.last_frame());
+                let left_frame = self.frames[left_frame_key].unwrap_meta_mut();
 
                 // alternative that might work (semi-pseudocode) and _might_ even be faster
                 // (especially when exploiting SIMD for the bulk addition):
                 // left_frame.distances.distances += right_frame.distances.splice(0..0, left_frame.len()).distances
                 // same goes for below with the Base case
-                left_frame.distances.increase_distance(left_frame.len() - 1, distance_between_frames);
+                left_frame.distances.increase_distance(left_len - 1, distance_between_frames);
+                left_frame.counts.increase_distance(left_len - 1, count_of_last_frame);
                 for i in 0..right_frame.len() - 1 {
-                    left_frame.distances.increase_distance(left_frame.len() - 1 + i + 1, right_frame.distances.distance(i))
+                    left_frame.distances.increase_distance(left_len + i, right_frame.distances.distance(i));
+                    left_frame.counts.increase_distance(left_len + i, right_frame.counts.distance(i));
                 }
-                
+
                 left_frame.frames.try_extend_from_slice(right_frame.frames.as_slice()).unwrap();
             }
-            EitherFrame::Base(left_frame) => {
+            EitherFrame::Base(_) => {
                 let right_frame = right_frame.unwrap_base_owned();
+                let left_frame = self.frames[left_frame_key].unwrap_base_mut();
 
-                left_frame.distances.increase_distance(left_frame.len() - 1, distance_between_frames);
+                left_frame.distances.increase_distance(left_len - 1, distance_between_frames);
                 for i in 0..right_frame.len() - 1 {
-                    left_frame.distances.increase_distance(left_frame.len() - 1 + i + 1, right_frame.distances.distance(i))
+                    left_frame.distances.increase_distance(left_len + i, right_frame.distances.distance(i));
                 }
-                
+
                 left_frame.keys.try_extend_from_slice(right_frame.keys.as_slice()).unwrap();
             }
         }
+        self.reindex(left_frame_key, left_len);
 
         let meta_frame = self.frames[meta_frame_key].unwrap_meta_mut();
+        let len = meta_frame.frames.len();
+        meta_frame.distances.remove_index(right_index, len);
+        meta_frame.counts.remove_index(right_index, len);
         meta_frame.frames.remove(right_index);
-        let distance_between_left_and_right = meta_frame.distances.distance(left_index);
-        meta_frame.distances.remove(right_index);
-        meta_frame.distances.increase_distance(left_index, distance_between_left_and_right);
+        self.reindex(meta_frame_key, right_index);
 
         Ok(())
     }
 
-    fn lower(&mut self, embedding: Embedding) {
-        if let Embedding::InMetaFrame(in_meta_frame) = embedding {
-            let frame = self.frames[in_meta_frame.frame].unwrap_meta_mut();
-            frame.level -= 1;
-            let frame = self.frames[in_meta_frame.frame].unwrap_meta();
-            self.lower(frame.embedding);
-        }
-    }
-
     fn try_merge_around(&mut self, key: FrameKey) {
         if let Embedding::InMetaFrame(in_meta_frame) = self.frames[key].embedding() {
             let index = in_meta_frame.index;
@@ -83,89 +81,172 @@ impl<P: Position, E: Element> PointList<P, E> {
                 // one key / frame was removed
                 // we might now be able to merge around the meta frame
                 self.try_merge_around(in_meta_frame.frame);
-                // and now we might be able to dissolve the meta frame
-                if self.frames[in_meta_frame.frame].unwrap_meta().frames.len() == 1 {
-                    let meta_frame = self.frames.remove(in_meta_frame.frame).unwrap().unwrap_meta_owned();
-                    self.lower(meta_frame.embedding);
-                    *self.frames[meta_frame.frames[0]].embedding_mut() = meta_frame.embedding;
-                }
             }
         } else {
             // nothing can be merged
         }
     }
 
-    fn replenish_distance(&mut self, IndexInFrame { index, frame }: IndexInFrame, distance: P) {
-        let distances = self.frames[frame].distances_mut();
-        if index > 0 {
-            distances.increase_distance(index - 1, distance);
-        } else if let Embedding::InMetaFrame(index_in_meta_frame) = self.frames[frame].embedding() {
-            self.replenish_distance(index_in_meta_frame, distance);
+    /// Replaces the frame at `frame_key`, which may not be embedded in a meta frame, by its only
+    /// frame for as long as it only has one frame. Returns the key of the remaining frame.
+    pub(crate) fn lower(&mut self, mut frame_key: FrameKey) -> FrameKey {
+        while let EitherFrame::Meta(frame) = &self.frames[frame_key] {
+            if frame.len() > 1 {
+                break;
+            }
+            let child_key = frame.first_frame();
+            self.frames.remove(frame_key);
+            self.frames[child_key].embed(Embedding::InList);
+            frame_key = child_key;
         }
+        frame_key
     }
 
-    pub fn remove_element(&mut self, key: PointKey) -> Option<E> {
-        if self.is_empty() { return None; }
+    /// Replaces the root by its only frame for as long as it only has one frame.
+    pub(crate) fn lower_root(&mut self) {
+        self.root = Some(self.lower(self.root.unwrap()));
+    }
+
+    /// Accounts for the first point of the frame at `frame_key` having moved forward by `distance`
+    /// in the frames containing it.
+    fn first_point_moved_forward(&mut self, frame_key: FrameKey, distance: P) {
+        match self.frames[frame_key].embedding() {
+            Embedding::InMetaFrame(IndexInFrame { frame, index }) => {
+                let meta_frame = self.frames[frame].unwrap_meta_mut();
+                if index + 1 < meta_frame.len() {
+                    meta_frame.distances.decrease_distance(index, distance);
+                }
+                if index > 0 {
+                    meta_frame.distances.increase_distance(index - 1, distance);
+                } else {
+                    self.first_point_moved_forward(frame, distance);
+                }
+            }
+            Embedding::InList => self.start += distance,
+        }
+    }
+
+    /// Removes the key or frame at `index_in_frame` from its frame. If this leaves the frame empty,
+    /// the frame itself is removed from its meta frame, and so on.
+    ///
+    /// Returns the key of the frame that the last key or frame was removed from.
+    fn remove_from_frame(&mut self, IndexInFrame { frame: frame_key, index }: IndexInFrame) -> FrameKey {
+        let frame = &mut self.frames[frame_key];
+        let len = frame.len();
+
+        if len == 1 {
+            let Embedding::InMetaFrame(index_in_meta_frame) = frame.embedding() else {
+                unreachable!("the root can only become empty when the list does")
+            };
+            self.frames.remove(frame_key);
+            return self.remove_from_frame(index_in_meta_frame);
+        }
+
+        let distance_to_next = frame.distances_mut().remove_index(index, len);
+        match frame {
+            EitherFrame::Meta(frame) => {
+                frame.counts.remove_index(index, len);
+                frame.frames.remove(index);
+            }
+            EitherFrame::Base(frame) => {
+                frame.keys.remove(index);
+            }
+        }
+        self.reindex(frame_key, index);
+
+        if index == 0 {
+            self.first_point_moved_forward(frame_key, distance_to_next);
+        }
 
-        let index_in_frame_of_removed = self.point_indices.get(key)?;
-        let frame_key_of_removed = index_in_frame_of_removed.frame;
-        let index_of_removed = index_in_frame_of_removed.index;
-        // if self were empty, we would have returned None earlier
-        let first_key = self.first_key().unwrap();
-        let last_key = self.last_key().unwrap();
+        frame_key
+    }
 
-        // don't return None after this, as then len would have been decreased without an element
-        // having been removed!
+    /// Removes the point at `index_in_frame` from the frames of the index, repairing the distances
+    /// around it. Its key is left untouched.
+    pub(crate) fn detach(&mut self, index_in_frame: IndexInFrame) {
         self.len -= 1;
 
-        /// if `key` is the only key in the list, the list should be reverted to the same state it
-        /// was in directly after initialisation
-        if key == first_key && key == last_key {
+        /// if the point was the only point in the index, the index should be reverted to the same
+        /// state it was in directly after initialisation
+        if self.len == 0 {
             self.root = None;
+            self.last_base_frame = None;
             self.frames.clear();
             self.start = zero();
             self.end = zero();
-            self.point_indices.clear();
-            return Some(self.elements.remove(key).unwrap());
+            return;
         }
 
-        let frame_of_removed = self.frames[frame_key_of_removed].unwrap_base_mut();
-        let distance_after_removed = frame_of_removed.distances.distance(index_of_removed);
+        // the counts have to be updated before the structure changes, so that the number of points
+        // in a frame is zero by the time the frame is removed for being empty
+        self.decrease_counts(index_in_frame.frame, 1);
 
-        /// if `key` is the first key in the list, adjust `self.start`
-        if key == first_key {
-            self.start += distance_after_removed;
-        }
+        let frame_key = self.remove_from_frame(index_in_frame);
 
-        /// if `key` is the last key in the list, adjust `self.end`
-        if key == last_key {
-            let distance_before_removed = frame_of_removed.distances.distance(index_of_removed - 1);
-            self.end -= distance_before_removed;
-        }
-
-        frame_of_removed.distances.remove(index_of_removed);
+        // join frames to prevent excessive fragmentation of the PointList's structure
+        self.try_merge_around(frame_key);
+        self.lower_root();
+        self.cache_last_base_frame();
 
-        self.replenish_distance(*index_in_frame_of_removed, distance_after_removed);
+        self.end = self.start + self.length_of(self.root.unwrap());
+    }
 
-        // redeclaration because it makes the borrow checker happy
-        let frame_of_removed = self.frames[frame_key_of_removed].unwrap_base_mut();
+    /// Removes the point at `key`, returning its position, or `None` if there is no point at `key`.
+    ///
+    /// Finding the position walks up to the root, see [`Self::remove_key`] to skip it.
+    pub fn remove_point(&mut self, key: PointKey) -> Option<P> {
+        let position = self.position(key)?;
+        self.remove_key(key);
+        Some(position)
+    }
 
-        // remove key
-        frame_of_removed.keys.remove(index_of_removed);
+    /// Removes the point at `key` without finding its position, returning whether there was a
+    /// point at `key`.
+    pub fn remove_key(&mut self, key: PointKey) -> bool {
+        let Some(index_in_frame) = self.point_indices.remove(key) else { return false; };
+        self.detach(index_in_frame);
+        true
+    }
 
-        // update point_indices
-        self.point_indices.remove(key);
-        for (index, &point_key) in frame_of_removed.keys.iter().enumerate().skip(index_of_removed) {
-            self.point_indices[point_key].index = index;
+    /// Removes every point for which `keep` returns false, visiting the points in positional
+    /// order.
+    ///
+    /// Instead of removing the points one by one, the frames are rebuilt from the remaining points
+    /// once, which takes linear time.
+    pub fn retain(&mut self, mut keep: impl FnMut(PointKey, P) -> bool) {
+        // nothing is changed before `keep` has been called for every point, such that the index
+        // stays intact if it panics
+        let (kept, removed): (Vec<_>, Vec<_>) = self.iter()
+            .partition(|&(point_key, position)| keep(point_key, position));
+
+        if !removed.is_empty() {
+            for (point_key, _) in removed {
+                self.point_indices.remove(point_key);
+            }
+            self.build_frames(kept);
         }
+    }
+}
 
-        self.try_merge_around(frame_key_of_removed);
-
-        // TODO if possible, join frame with the next / previous frame to prevent excessive
-        //  fragmentation of the PointList's structure (should be done)
-
-        // FIXME this function does not yet protect against the creation of empty frames! (should be done)
+impl<P: Position, E: Element> PointList<P, E> {
+    pub fn remove_element(&mut self, key: PointKey) -> Option<E> {
+        self.index.remove_key(key).then(|| self.elements.remove(key).unwrap())
+    }
 
-        Some(self.elements.remove(key).unwrap())
+    /// Removes every point for which `keep` returns false, visiting the points in positional
+    /// order, see [`PointIndex::retain`].
+    pub fn retain(&mut self, mut keep: impl FnMut(PointKey, P, &mut E) -> bool) {
+        let elements = &mut self.elements;
+        let mut removed = vec![];
+        self.index.retain(|point_key, position| {
+            let kept = keep(point_key, position, &mut elements[point_key]);
+            if !kept {
+                removed.push(point_key);
+            }
+            kept
+        });
+        for point_key in removed {
+            self.elements.remove(point_key);
+        }
     }
 }
@@ -0,0 +1,124 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use num_traits::zero;
+use crate::{Element, IndexInFrame, PointIndex, PointKey, PointList, Position};
+use crate::point_list::Direction;
+
+/// The reason why the points after a point could not be shifted.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum ShiftError {
+    /// The given key does not belong to a point in the list.
+    UnknownKey,
+    /// The next point would be moved past the point at the given key.
+    GapTooSmall,
+    /// The point at the given key has no neighbour on the side of the gap.
+    NoNeighbour,
+    /// The given distance is negative.
+    NegativeDistance,
+}
+
+impl Display for ShiftError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ShiftError::UnknownKey =>
+                f.write_str("the key does not belong to a point in the list"),
+            ShiftError::GapTooSmall =>
+                f.write_str("the next point would be moved past the point at the key"),
+            ShiftError::NoNeighbour =>
+                f.write_str("the point at the key has no neighbour on the side of the gap"),
+            ShiftError::NegativeDistance =>
+                f.write_str("the distance is negative"),
+        }
+    }
+}
+
+impl Error for ShiftError {}
+
+impl<P: Position> PointIndex<P> {
+    /// Moves every point after the point at `key` by `delta` towards greater positions. Fails with
+    /// [`ShiftError::NegativeDistance`] if `delta` is negative.
+    ///
+    /// Only the distances between the point at `key` and the next point are changed, so this takes
+    /// logarithmic time regardless of the number of points moved.
+    pub fn shift_after(&mut self, key: PointKey, delta: P) -> Result<(), ShiftError> {
+        if delta < zero() {
+            return Err(ShiftError::NegativeDistance);
+        }
+
+        let index_in_frame = *self.point_indices.get(key).ok_or(ShiftError::UnknownKey)?;
+        self.move_after(index_in_frame, delta, Direction::Later);
+        Ok(())
+    }
+
+    /// Moves every point after the point at `key` by `delta` towards greater positions, or towards
+    /// smaller ones if `delta` is negative. The distance between the point at `key` and the next
+    /// point may shrink down to, but not below, zero.
+    pub fn shift_after_signed(&mut self, key: PointKey, delta: P) -> Result<(), ShiftError> {
+        if delta >= zero() {
+            return self.shift_after(key, delta);
+        }
+
+        let index_in_frame = *self.point_indices.get(key).ok_or(ShiftError::UnknownKey)?;
+        let shrinkage = zero::<P>() - delta;
+        if let Some((_, distance_to_next)) = self.next_of(index_in_frame) {
+            if shrinkage > distance_to_next {
+                return Err(ShiftError::GapTooSmall);
+            }
+            self.move_after(index_in_frame, shrinkage, Direction::Earlier);
+        }
+        Ok(())
+    }
+
+    /// Sets the distance between the point at `key` and the next point to `distance`, moving every
+    /// later point along with the next one. Returns the previous distance, or fails with
+    /// [`ShiftError::NegativeDistance`] if `distance` is negative.
+    pub fn set_distance_to_next(&mut self, key: PointKey, distance: P) -> Result<P, ShiftError> {
+        let index_in_frame = *self.point_indices.get(key).ok_or(ShiftError::UnknownKey)?;
+        self.set_distance_after(index_in_frame, distance)
+    }
+
+    /// Sets the distance between the point at `key` and the previous point to `distance`, moving
+    /// the point at `key` and every later point. Returns the previous distance, or fails with
+    /// [`ShiftError::NegativeDistance`] if `distance` is negative.
+    pub fn set_distance_to_prev(&mut self, key: PointKey, distance: P) -> Result<P, ShiftError> {
+        let index_in_frame = *self.point_indices.get(key).ok_or(ShiftError::UnknownKey)?;
+        let (previous, _) = self.previous_of(index_in_frame).ok_or(ShiftError::NoNeighbour)?;
+        self.set_distance_after(previous, distance)
+    }
+
+    fn set_distance_after(&mut self, index_in_frame: IndexInFrame, distance: P) -> Result<P, ShiftError> {
+        if distance < zero() {
+            return Err(ShiftError::NegativeDistance);
+        }
+
+        let (_, old_distance) = self.next_of(index_in_frame).ok_or(ShiftError::NoNeighbour)?;
+        if distance > old_distance {
+            self.move_after(index_in_frame, distance - old_distance, Direction::Later);
+        } else {
+            self.move_after(index_in_frame, old_distance - distance, Direction::Earlier);
+        }
+        Ok(old_distance)
+    }
+}
+
+impl<P: Position, E: Element> PointList<P, E> {
+    /// See [`PointIndex::shift_after`].
+    pub fn shift_after(&mut self, key: PointKey, delta: P) -> Result<(), ShiftError> {
+        self.index.shift_after(key, delta)
+    }
+
+    /// See [`PointIndex::shift_after_signed`].
+    pub fn shift_after_signed(&mut self, key: PointKey, delta: P) -> Result<(), ShiftError> {
+        self.index.shift_after_signed(key, delta)
+    }
+
+    /// See [`PointIndex::set_distance_to_next`].
+    pub fn set_distance_to_next(&mut self, key: PointKey, distance: P) -> Result<P, ShiftError> {
+        self.index.set_distance_to_next(key, distance)
+    }
+
+    /// See [`PointIndex::set_distance_to_prev`].
+    pub fn set_distance_to_prev(&mut self, key: PointKey, distance: P) -> Result<P, ShiftError> {
+        self.index.set_distance_to_prev(key, distance)
+    }
+}
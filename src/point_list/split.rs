@@ -0,0 +1,126 @@
+use std::mem;
+use slotmap::SecondaryMap;
+use crate::{EitherFrame, Element, Embedding, Frame, FrameKey, IndexInFrame, PointIndex, PointList, Position};
+
+impl<P: Position> PointIndex<P> {
+    /// Cuts the frames along the path to the point at `index_in_frame`, moving every later point
+    /// into a separate tree within the same frames. Returns the root of that tree together with the
+    /// distance between the first point of the index and the first point of the tree.
+    ///
+    /// There must be a point after the one at `index_in_frame`. Counts and distances are only kept
+    /// consistent within the two trees, not for the index as a whole.
+    pub(crate) fn cut_after(&mut self, IndexInFrame { frame: base_frame_key, index }: IndexInFrame) -> (FrameKey, P) {
+        // the frame holding the points that are split off, and the distance between the first point
+        // of the frame that they were split off from and its own first point
+        let mut split_off = None;
+        let base_frame = self.frames[base_frame_key].unwrap_base_mut();
+        if index + 1 < base_frame.len() {
+            let (frame, distance) = base_frame.split_off(index + 1, Embedding::InList);
+            let frame_key = self.frames.insert(frame.into());
+            self.reindex(frame_key, 0);
+            split_off = Some((frame_key, distance));
+        }
+
+        let mut frame_key = base_frame_key;
+        while let Embedding::InMetaFrame(IndexInFrame { frame: meta_frame_key, index }) =
+            self.frames[frame_key].embedding() {
+            let meta_frame = self.frames[meta_frame_key].unwrap_meta_mut();
+            let position = meta_frame.distances.position(index);
+            let level = meta_frame.level;
+
+            split_off = if index + 1 < meta_frame.len() {
+                let (mut frame, next_position) = meta_frame.split_off(index + 1, Embedding::InList);
+                let distance = if let Some((split_off_key, distance)) = split_off {
+                    let count = self.count_of(split_off_key);
+                    frame.insert_first_frame(split_off_key, next_position - position - distance, count);
+                    position + distance
+                } else {
+                    next_position
+                };
+                let frame_key = self.frames.insert(frame.into());
+                self.reindex(frame_key, 0);
+                Some((frame_key, distance))
+            } else {
+                // the frame is the last one in its meta frame, so the split off points only need
+                // to be brought to the same level
+                split_off.map(|(split_off_key, distance)|
+                    (self.wrap_frame(split_off_key, level), position + distance))
+            };
+
+            frame_key = meta_frame_key;
+        }
+
+        // the last point was excluded, so something must have been split off
+        split_off.unwrap()
+    }
+
+    /// The keys of the frame at `frame_key` and all frames below it.
+    pub(crate) fn frames_below(&self, frame_key: FrameKey) -> SecondaryMap<FrameKey, ()> {
+        let mut frame_keys = SecondaryMap::new();
+        let mut stack = vec![frame_key];
+        while let Some(frame_key) = stack.pop() {
+            frame_keys.insert(frame_key, ());
+            if let EitherFrame::Meta(frame) = &self.frames[frame_key] {
+                stack.extend(frame.frames.iter().copied());
+            }
+        }
+        frame_keys
+    }
+
+    /// Moves every point at or after `position` into a new index, in which they keep their keys and
+    /// positions. The keys of the kept points stay valid in this index, while the keys of the moved
+    /// points are only valid in the new one.
+    ///
+    /// Cutting the frames takes logarithmic time, but as both indices have to keep recognising the
+    /// keys of their points and each index hands out its keys from its own slot map, the key maps
+    /// are copied, which takes linear time.
+    pub fn split_off(&mut self, position: P) -> Self {
+        let Some((last_kept, _)) = self.find_last(|other_position| other_position < position) else {
+            // every point is split off, the keys are kept such that they aren't handed out again
+            let mut point_indices = self.point_indices.clone();
+            point_indices.clear();
+            return mem::replace(self, Self { point_indices, ..Self::new() });
+        };
+        if last_kept == self.point_indices[self.last_key().unwrap()] {
+            return Self::new();
+        }
+
+        let (split_off_root, distance) = self.cut_after(last_kept);
+        let split_off_frames = self.frames_below(split_off_root);
+        let mut split_off = Self {
+            frames: self.frames.clone(),
+            root: Some(split_off_root),
+            last_base_frame: None,
+            start: self.start + distance,
+            end: self.end,
+            len: self.count_of(split_off_root),
+            point_indices: self.point_indices.clone(),
+        };
+
+        self.frames.retain(|frame_key, _| !split_off_frames.contains_key(frame_key));
+        split_off.frames.retain(|frame_key, _| split_off_frames.contains_key(frame_key));
+        self.point_indices.retain(|_, index_in_frame| !split_off_frames.contains_key(index_in_frame.frame));
+        split_off.point_indices.retain(|_, index_in_frame| split_off_frames.contains_key(index_in_frame.frame));
+        self.len -= split_off.len;
+        self.last_base_frame = Some(last_kept.frame);
+
+        self.lower_root();
+        split_off.lower_root();
+        split_off.cache_last_base_frame();
+        self.end = self.start + self.length_of(self.root.unwrap());
+
+        split_off
+    }
+}
+
+impl<P: Position, E: Element> PointList<P, E> {
+    /// Moves every point at or after `position` into a new list, in which they keep their keys,
+    /// positions and elements, see [`PointIndex::split_off`].
+    pub fn split_off(&mut self, position: P) -> Self {
+        let index = self.index.split_off(position);
+        let elements = index.point_indices.keys()
+            .map(|point_key| (point_key, self.elements.remove(point_key).unwrap()))
+            .collect();
+        Self { index, elements }
+    }
+}
@@ -1,6 +1,74 @@
-use crate::{Element, PointKey, PointList, Position};
+use std::collections::{BTreeMap, HashSet};
+use std::ops::{Bound, Range, RangeBounds};
+use itertools::Itertools;
+use rand::prelude::*;
+use crate::{Cursor, EitherFrame, Element, Embedding, Entry, FloatPosition, Frame, IndexInFrame, InsertError, PointIndex, PointKey, PointList, Position, RangeGap, ShiftError, DISTANCES_CAPACITY, FRAME_CAPACITY};
 use num_traits::zero;
 
+fn check_index_invariants<P: Position>(list: &PointIndex<P>) {
+    let Some(root) = list.root else {
+        assert_eq!(list.last_base_frame, None);
+        assert!(list.frames.is_empty());
+        assert!(list.point_indices.is_empty());
+        assert_eq!(list.len, 0);
+        return;
+    };
+
+    assert_eq!(list.frames[root].embedding(), Embedding::InList);
+
+    let mut embedded_frames = 0;
+    let mut keys = 0;
+    for (frame_key, frame) in &list.frames {
+        assert!(frame.len() > 0);
+        for index in frame.len() - 1..DISTANCES_CAPACITY {
+            assert!(frame.distances().distance(index).is_zero());
+        }
+        match frame {
+            EitherFrame::Meta(frame) => {
+                for (index, &child) in frame.frames.iter().enumerate() {
+                    let embedding = Embedding::InMetaFrame(IndexInFrame::new(frame_key, index));
+                    assert_eq!(list.frames[child].embedding(), embedding);
+                    assert_eq!(list.frames[child].level() + 1, frame.level);
+                    if index + 1 < frame.len() {
+                        assert_eq!(frame.counts.distance(index), list.count_of(child));
+                    }
+                    embedded_frames += 1;
+                }
+                for index in frame.len() - 1..DISTANCES_CAPACITY {
+                    assert!(frame.counts.distance(index).is_zero());
+                }
+            }
+            EitherFrame::Base(frame) => {
+                for (index, &key) in frame.keys.iter().enumerate() {
+                    assert_eq!(list.point_indices[key], IndexInFrame::new(frame_key, index));
+                    keys += 1;
+                }
+            }
+        }
+    }
+
+    assert_eq!(embedded_frames + 1, list.frames.len());
+    assert_eq!(keys, list.len);
+    assert_eq!(list.point_indices.len(), list.len);
+    assert_eq!(list.end, list.start + list.length_of(root));
+    assert_eq!(list.count_of(root), list.len);
+
+    let mut last_base_frame = root;
+    while let EitherFrame::Meta(frame) = &list.frames[last_base_frame] {
+        last_base_frame = frame.last_frame();
+    }
+    assert_eq!(list.last_base_frame, Some(last_base_frame));
+}
+
+fn check_invariants<P: Position, E: Element>(list: &PointList<P, E>) {
+    check_index_invariants(&list.index);
+
+    assert_eq!(list.elements.len(), list.len());
+    for key in list.index.point_indices.keys() {
+        assert!(list.elements.contains_key(key));
+    }
+}
+
 /*#[test]
 fn test_add_element_and_position() {
     let mut list = PointList::<usize, char>::new();
@@ -193,6 +261,46 @@ fn list_from_array<P: Position, E: Element, const N: usize>(
     (list, keys)
 }
 
+/// A list of points at random positions within `positions`, one for each of `len` tries that
+/// hits a free position, whose elements count the tries. Returns the list together with the keys
+/// and elements of its points by their positions.
+fn random_list(rng: &mut StdRng, len: usize, positions: Range<u32>) -> (PointList<u32, usize>, BTreeMap<u32, (PointKey, usize)>) {
+    let mut list = PointList::new();
+    let mut reference = BTreeMap::new();
+    for i in 0..len {
+        let position = rng.gen_range(positions.clone());
+        reference.entry(position).or_insert_with(|| (list.insert_element(i, position), i));
+    }
+    (list, reference)
+}
+
+/// The keys, positions and elements of points, in positional order.
+type Points = Vec<(PointKey, i64, usize)>;
+
+/// A list of points that are added one after the other at random distances, with `elements` as
+/// their elements. Returns the list together with the keys, positions and elements of its points.
+fn random_gaps(rng: &mut StdRng, elements: Range<usize>) -> (PointList<i64, usize>, Points) {
+    let mut list = PointList::new();
+    let mut points = vec![];
+    for element in elements {
+        let key = list.add_element(element, rng.gen_range(1..20i64));
+        points.push((key, list.end(), element));
+    }
+    (list, points)
+}
+
+/// Adds points to both ends of a list that was just changed in bulk and checks that it stays
+/// consistent.
+fn check_still_usable(rng: &mut StdRng, list: &mut PointList<i64, usize>) {
+    for _ in 0..20 {
+        let key = list.push_front(0, rng.gen_range(1..20)).unwrap();
+        assert_eq!(list.first_key(), Some(key));
+        let key = list.add_element(0, rng.gen_range(1..20));
+        assert_eq!(list.last_key(), Some(key));
+    }
+    check_invariants(list);
+}
+
 #[test]
 fn test_add_element_remove_element() {
     let (mut list1, [a1, ..]) = list_from_array([
@@ -204,8 +312,1254 @@ fn test_add_element_remove_element() {
         ('f', 13),
     ]);
     let (list2, [..]) = list_from_array([('b', 6), ('c', 9), ('d', 10), ('e', 12), ('f', 13)]);
+    std::fs::create_dir_all("out").unwrap();
     std::fs::write("out/list1_original.txt", format!("{:?}", list1)).unwrap();
     list1.remove_element(a1);
     std::fs::write("out/list1.txt", format!("{:?}", list1)).unwrap();
     std::fs::write("out/list2.txt", format!("{:?}", list2)).unwrap();
 }
+
+#[test]
+fn test_insert_element() {
+    let (mut list, [b, d]) = list_from_array([('b', 6), ('d', 10)]);
+
+    let c = list.insert_element('c', 9);
+    let e = list.insert_element('e', 12);
+    let a = list.insert_element('a', 4);
+    check_invariants(&list);
+
+    assert_eq!(list.len(), 5);
+    assert_eq!(list.start(), 4);
+    assert_eq!(list.end(), 12);
+    for (key, element, position) in [(a, 'a', 4), (b, 'b', 6), (c, 'c', 9), (d, 'd', 10), (e, 'e', 12)] {
+        assert_eq!(list.element(key), Some(&element));
+        assert_eq!(list.position(key), Some(position));
+    }
+    assert_eq!(list.first_key(), Some(a));
+    assert_eq!(list.last_key(), Some(e));
+
+    // splits the full root
+    let f = list.insert_element('f', 7);
+    check_invariants(&list);
+    assert_eq!(list.position(f), Some(7));
+    assert_eq!(list.position(c), Some(9));
+}
+
+#[test]
+fn test_insert_element_at_occupied_position() {
+    let (mut list, _) = list_from_array([('a', 4), ('b', 6)]);
+    let b = list.key_at(6).unwrap();
+    let c = list.insert_element('c', 6);
+    list.insert_element('d', 6);
+    check_invariants(&list);
+    assert_eq!(list.position(c), Some(6));
+    assert_eq!(list.elements().copied().collect_vec(), vec!['a', 'b', 'c', 'd']);
+    assert_eq!(list.key_at(6), Some(b));
+    assert_eq!(list.distance_to_next(b), Some(0));
+}
+
+#[test]
+fn test_insert_element_random() {
+    let mut rng = StdRng::seed_from_u64(0);
+    let mut list = PointList::new();
+    let mut reference = BTreeMap::new();
+    for i in 0..2000 {
+        let position = loop {
+            let position = rng.gen_range(0..1_000_000u64);
+            if !reference.contains_key(&position) {
+                break position;
+            }
+        };
+        let key = list.insert_element(i, position);
+        reference.insert(position, key);
+    }
+    check_invariants(&list);
+
+    assert_eq!(list.len(), reference.len());
+    assert_eq!(list.start(), *reference.first_key_value().unwrap().0);
+    assert_eq!(list.end(), *reference.last_key_value().unwrap().0);
+    assert_eq!(list.first_key(), Some(*reference.first_key_value().unwrap().1));
+    assert_eq!(list.last_key(), Some(*reference.last_key_value().unwrap().1));
+    for (&position, &key) in &reference {
+        assert_eq!(list.position(key), Some(position));
+    }
+}
+
+#[test]
+fn test_insert_after_and_before() {
+    let (mut list, [a, b, c]) = list_from_array([('a', 4), ('b', 6), ('c', 9)]);
+
+    assert_eq!(list.insert_after(b, 4, 'x'), Err(InsertError::CrossesNeighbour));
+    assert_eq!(list.insert_before(b, 3, 'x'), Err(InsertError::CrossesNeighbour));
+
+    let after_c = list.insert_after(c, 2, 'd').unwrap();
+    let before_a = list.insert_before(a, 3, 'z').unwrap();
+    let after_b = list.insert_after(b, 2, 'y').unwrap();
+    let before_b = list.insert_before(b, 1, 'w').unwrap();
+    check_invariants(&list);
+
+    assert_eq!(list.start(), 1);
+    assert_eq!(list.end(), 11);
+    assert_eq!(list.first_key(), Some(before_a));
+    assert_eq!(list.last_key(), Some(after_c));
+    assert_eq!(list.position(before_b), Some(5));
+    assert_eq!(list.position(after_b), Some(8));
+    assert_eq!(list.position(c), Some(9));
+
+    list.remove_element(c);
+    assert_eq!(list.insert_after(c, 1, 'x'), Err(InsertError::UnknownKey));
+}
+
+#[test]
+fn test_insert_after_and_before_random() {
+    let mut rng = StdRng::seed_from_u64(1);
+    let (mut list, keys) = list_from_array::<u64, usize, 100>(core::array::from_fn(|i| (i, (i as u64 + 1) << 20)));
+    let mut reference: BTreeMap<u64, PointKey> = keys.iter().map(|&key| (list.position(key).unwrap(), key)).collect();
+    for i in 0..2000 {
+        let (&position, &key) = reference.iter().nth(rng.gen_range(0..reference.len())).unwrap();
+        if rng.gen() {
+            let next = reference.range(position + 1..).next().map(|(&next, _)| next);
+            let gap = next.map_or(1000, |next| next - position);
+            if gap == 1 {
+                continue;
+            }
+            let distance = rng.gen_range(1..gap);
+            let new_key = list.insert_after(key, distance, i).unwrap();
+            reference.insert(position + distance, new_key);
+        } else {
+            let previous = reference.range(..position).next_back().map(|(&previous, _)| previous);
+            let gap = position - previous.unwrap_or(0);
+            if gap == 1 {
+                continue;
+            }
+            let distance = rng.gen_range(1..gap);
+            let new_key = list.insert_before(key, distance, i).unwrap();
+            reference.insert(position - distance, new_key);
+        }
+    }
+    check_invariants(&list);
+
+    assert_eq!(list.start(), *reference.first_key_value().unwrap().0);
+    assert_eq!(list.end(), *reference.last_key_value().unwrap().0);
+    for (&position, &key) in &reference {
+        assert_eq!(list.position(key), Some(position));
+    }
+}
+
+#[test]
+fn test_push_front() {
+    let mut list = PointList::new();
+    let first = list.add_element('a', 3i64);
+    assert_eq!(list.start(), 3);
+    assert_eq!(list.position(first), Some(3));
+
+    let mut keys = vec![first];
+    for i in 1..1000 {
+        keys.push(list.push_front('b', 2).unwrap());
+        assert_eq!(list.start(), 3 - 2 * i);
+        assert_eq!(list.first_key(), keys.last().copied());
+    }
+    let last = list.add_element('c', 5);
+    check_invariants(&list);
+
+    assert_eq!(list.end(), 8);
+    assert_eq!(list.position(last), Some(8));
+    for (i, &key) in keys.iter().enumerate() {
+        assert_eq!(list.position(key), Some(3 - 2 * i as i64));
+    }
+    assert_eq!(list.push_front('d', -1), Err(InsertError::NegativeDistance));
+    assert_eq!(list.insert_after(first, -1, 'd'), Err(InsertError::NegativeDistance));
+    assert_eq!(list.insert_before(first, -1, 'd'), Err(InsertError::NegativeDistance));
+    check_invariants(&list);
+
+    // without a first point, the distance has nothing to be measured from
+    let mut list = PointList::new();
+    let only = list.push_front('a', 5u32).unwrap();
+    assert_eq!(list.position(only), Some(0));
+    let first = list.push_front('b', 0).unwrap();
+    assert_eq!(list.keys().collect_vec(), vec![first, only]);
+    check_invariants(&list);
+}
+
+#[test]
+fn test_push_front_and_add_element() {
+    let mut rng = StdRng::seed_from_u64(2);
+    let mut list = PointList::new();
+    let mut reference = std::collections::VecDeque::new();
+    for i in 0..3000 {
+        let distance = rng.gen_range(1..100i64);
+        if rng.gen() {
+            reference.push_front((list.push_front(i, distance).unwrap(), list.start()));
+        } else {
+            reference.push_back((list.add_element(i, distance), list.end()));
+        }
+    }
+    check_invariants(&list);
+
+    for &(key, position) in &reference {
+        assert_eq!(list.position(key), Some(position));
+    }
+}
+
+#[test]
+fn test_key_at() {
+    let (list, reference) = random_list(&mut StdRng::seed_from_u64(3), 2000, 0..10_000);
+    for position in 0..10_001 {
+        assert_eq!(list.key_at(position), reference.get(&position).map(|&(key, _)| key));
+    }
+    assert_eq!(PointList::<u32, ()>::new().key_at(0), None);
+}
+
+#[test]
+fn test_nearest_keys() {
+    let (list, reference) = random_list(&mut StdRng::seed_from_u64(4), 2000, 100..10_000);
+    let swap = |(&position, &(key, _)): (&u32, &(PointKey, usize))| (key, position);
+    for position in 0..10_100 {
+        assert_eq!(list.key_at_or_before(position), reference.range(..=position).next_back().map(swap));
+        assert_eq!(list.key_before(position), reference.range(..position).next_back().map(swap));
+        assert_eq!(list.key_at_or_after(position), reference.range(position..).next().map(swap));
+        assert_eq!(list.key_after(position), reference.range(position + 1..).next().map(swap));
+    }
+
+    let empty = PointList::<u32, ()>::new();
+    assert_eq!(empty.key_at_or_before(0), None);
+    assert_eq!(empty.key_at_or_after(0), None);
+}
+
+#[test]
+fn test_range() {
+    let mut rng = StdRng::seed_from_u64(5);
+    let (list, reference) = random_list(&mut rng, 1000, 0..5000);
+    let expected = |range: (Bound<u32>, Bound<u32>)| {
+        reference.range(range).map(|(&position, &(key, element))| (key, position, element)).collect_vec()
+    };
+    let actual = |range: (Bound<u32>, Bound<u32>)| {
+        list.range(range).map(|(key, position, &element)| (key, position, element)).collect_vec()
+    };
+    for _ in 0..200 {
+        let start = rng.gen_range(0..5100u32);
+        let end = rng.gen_range(start..5100u32);
+        for range in [
+            (Bound::Included(start), Bound::Included(end)),
+            (Bound::Included(start), Bound::Excluded(end)),
+            (Bound::Excluded(start), Bound::Included(end)),
+            (Bound::Unbounded, Bound::Excluded(end)),
+            (Bound::Included(start), Bound::Unbounded),
+        ] {
+            assert_eq!(actual(range), expected(range));
+            let mut reversed = list.range(range).rev().map(|(key, position, &element)| (key, position, element)).collect_vec();
+            reversed.reverse();
+            assert_eq!(reversed, expected(range));
+        }
+    }
+    assert_eq!(actual((Bound::Unbounded, Bound::Unbounded)), expected((Bound::Unbounded, Bound::Unbounded)));
+    assert_eq!(list.range((Bound::Included(3000), Bound::Included(1000))).next(), None);
+
+    let mut range = list.range(..);
+    let (first, ..) = range.next().unwrap();
+    let (last, ..) = range.next_back().unwrap();
+    assert_eq!(Some(first), list.first_key());
+    assert_eq!(Some(last), list.last_key());
+    assert_eq!(range.count(), reference.len() - 2);
+}
+
+#[test]
+fn test_iter() {
+    let mut rng = StdRng::seed_from_u64(6);
+    let (list, reference) = random_list(&mut rng, 3000, 0..1_000_000);
+    let expected = reference.iter().map(|(&position, &(key, element))| (key, position, element)).collect_vec();
+    assert_eq!(list.iter().map(|(key, position, &element)| (key, position, element)).collect_vec(), expected);
+    assert_eq!(list.iter().rev().map(|(key, position, &element)| (key, position, element)).collect_vec(),
+               expected.iter().rev().copied().collect_vec());
+    assert_eq!(list.keys().collect_vec(), expected.iter().map(|&(key, _, _)| key).collect_vec());
+    assert_eq!(list.index().iter().collect_vec(), expected.iter().map(|&(key, position, _)| (key, position)).collect_vec());
+    assert_eq!(list.positions().collect_vec(), expected.iter().map(|&(_, position, _)| position).collect_vec());
+    assert_eq!(list.elements().copied().collect_vec(), expected.iter().map(|&(_, _, element)| element).collect_vec());
+
+    // alternate between both ends until they meet
+    let mut iter = list.keys();
+    let mut front = 0;
+    let mut back = expected.len();
+    loop {
+        let key = if rng.gen() {
+            front += 1;
+            iter.next()
+        } else {
+            back -= 1;
+            iter.next_back()
+        };
+        if front > back {
+            assert_eq!(key, None);
+            break;
+        }
+        assert!(key.is_some());
+    }
+
+    assert_eq!(PointList::<u32, ()>::new().iter().next(), None);
+}
+
+#[test]
+fn test_iter_mut() {
+    let (mut list, _) = random_list(&mut StdRng::seed_from_u64(7), 1000, 0..1_000_000);
+    let expected = list.iter().map(|(key, position, _)| (key, position)).collect_vec();
+    for (index, (key, position, element)) in list.iter_mut().enumerate() {
+        assert_eq!((key, position), expected[index]);
+        *element = index;
+    }
+    for element in list.elements_mut().rev() {
+        *element *= 2;
+    }
+    assert_eq!(list.elements().copied().collect_vec(), (0..expected.len()).map(|index| index * 2).collect_vec());
+
+    // the references from both ends may be held at the same time
+    let mut iter = list.iter_mut();
+    let (first_key, _, first) = iter.next().unwrap();
+    let (last_key, _, last) = iter.next_back().unwrap();
+    std::mem::swap(first, last);
+    assert_eq!(iter.count(), expected.len() - 2);
+    assert_eq!(list.element(first_key), Some(&(expected.len() * 2 - 2)));
+    assert_eq!(list.element(last_key), Some(&0));
+    assert_eq!(PointList::<u32, usize>::new().iter_mut().next(), None);
+}
+
+#[test]
+fn test_neighbours() {
+    let (mut list, _) = random_list(&mut StdRng::seed_from_u64(8), 2000, 0..1_000_000);
+    let points = list.iter().map(|(key, position, _)| (key, position)).collect_vec();
+    for (index, &(key, position)) in points.iter().enumerate() {
+        let next = points.get(index + 1);
+        let previous = index.checked_sub(1).map(|index| points[index]);
+        assert_eq!(list.next_key(key), next.map(|&(key, _)| key));
+        assert_eq!(list.distance_to_next(key), next.map(|&(_, next)| next - position));
+        assert_eq!(list.prev_key(key), previous.map(|(key, _)| key));
+        assert_eq!(list.distance_to_prev(key), previous.map(|(_, previous)| position - previous));
+    }
+
+    let (key, _) = points[0];
+    list.remove_element(key);
+    assert_eq!(list.next_key(key), None);
+    assert_eq!(list.distance_to_prev(key), None);
+}
+
+#[test]
+fn test_nth_and_index_of() {
+    let (mut list, _) = random_list(&mut StdRng::seed_from_u64(9), 3000, 0..1_000_000);
+    check_invariants(&list);
+
+    let keys = list.keys().collect_vec();
+    for (index, &key) in keys.iter().enumerate() {
+        assert_eq!(list.nth(index), Some(key));
+        assert_eq!(list.index_of(key), Some(index));
+    }
+    assert_eq!(list.nth(keys.len()), None);
+
+    list.remove_element(keys[10]);
+    assert_eq!(list.index_of(keys[10]), None);
+    assert_eq!(list.index_of(keys[11]), Some(10));
+    assert_eq!(list.nth(10), Some(keys[11]));
+}
+
+#[test]
+fn test_remove_element_random() {
+    let mut rng = StdRng::seed_from_u64(10);
+    let (mut list, mut reference) = random_list(&mut rng, 3000, 0..1_000_000);
+    while !reference.is_empty() {
+        let (&position, &(key, element)) = reference.iter().nth(rng.gen_range(0..reference.len())).unwrap();
+        assert_eq!(list.remove_element(key), Some(element));
+        assert_eq!(list.remove_element(key), None);
+        reference.remove(&position);
+
+        if reference.len() % 97 == 0 {
+            check_invariants(&list);
+            assert_eq!(list.len(), reference.len());
+            let expected = reference.iter().map(|(&position, &(key, _))| (key, position)).collect_vec();
+            assert_eq!(list.iter().map(|(key, position, _)| (key, position)).collect_vec(), expected);
+            for (index, &(key, position)) in expected.iter().enumerate() {
+                assert_eq!(list.position(key), Some(position));
+                assert_eq!(list.index_of(key), Some(index));
+            }
+        }
+    }
+    check_invariants(&list);
+    assert!(list.is_empty());
+}
+
+#[test]
+fn test_shift_after() {
+    let mut rng = StdRng::seed_from_u64(11);
+    let mut list = PointList::new();
+    let mut points = vec![];
+    for i in 0..2000 {
+        points.push((list.add_element(i, rng.gen_range(1..100i64)), list.end()));
+    }
+
+    for _ in 0..500 {
+        let index = rng.gen_range(0..points.len());
+        let (key, _) = points[index];
+        let delta = if rng.gen() {
+            let delta = rng.gen_range(0..50);
+            list.shift_after(key, delta).unwrap();
+            delta
+        } else {
+            let gap = points.get(index + 1).map_or(1000, |&(_, next)| next - points[index].1);
+            let delta = -rng.gen_range(0..=gap);
+            list.shift_after_signed(key, delta).unwrap();
+            delta
+        };
+        for (_, position) in &mut points[index + 1..] {
+            *position += delta;
+        }
+    }
+    check_invariants(&list);
+
+    assert_eq!(list.start(), points[0].1);
+    assert_eq!(list.end(), points.last().unwrap().1);
+    for &(key, position) in &points {
+        assert_eq!(list.position(key), Some(position));
+    }
+
+    let (first, _) = points[0];
+    let gap = points[1].1 - points[0].1;
+    assert_eq!(list.shift_after_signed(first, -gap - 1), Err(ShiftError::GapTooSmall));
+    assert_eq!(list.shift_after(first, -1), Err(ShiftError::NegativeDistance));
+    assert_eq!(list.position(points[1].0), Some(points[1].1));
+    list.remove_element(first);
+    assert_eq!(list.shift_after(first, 1), Err(ShiftError::UnknownKey));
+}
+
+#[test]
+fn test_relocate() {
+    let mut rng = StdRng::seed_from_u64(12);
+    let mut list = PointList::new();
+    let mut reference = BTreeMap::new();
+    for i in 0..1000 {
+        let key = list.add_element(i, rng.gen_range(1..100i64));
+        reference.insert(list.end(), (key, i));
+    }
+
+    for _ in 0..2000 {
+        let &old_position = reference.keys().nth(rng.gen_range(0..reference.len())).unwrap();
+        let new_position = rng.gen_range(-1000..100_000);
+        if old_position != new_position && reference.contains_key(&new_position) {
+            continue;
+        }
+
+        let (key, element) = reference.remove(&old_position).unwrap();
+        assert_eq!(list.relocate(key, new_position), Some(old_position));
+        reference.insert(new_position, (key, element));
+    }
+    check_invariants(&list);
+
+    assert_eq!(list.start(), *reference.keys().next().unwrap());
+    assert_eq!(list.end(), *reference.keys().next_back().unwrap());
+    for (&position, &(key, element)) in &reference {
+        assert_eq!(list.position(key), Some(position));
+        assert_eq!(list.element(key), Some(&element));
+    }
+    assert_eq!(list.iter().map(|(key, _, _)| key).collect_vec(), reference.values().map(|&(key, _)| key).collect_vec());
+
+    let mut list = PointList::new();
+    let key = list.add_element('a', 5);
+    assert_eq!(list.relocate(key, -3), Some(5));
+    assert_eq!((list.start(), list.end(), list.position(key)), (-3, -3, Some(-3)));
+    check_invariants(&list);
+    list.remove_element(key);
+    assert_eq!(list.relocate(key, 1), None);
+}
+
+#[test]
+fn test_set_distance() {
+    let mut rng = StdRng::seed_from_u64(13);
+    let mut list = PointList::new();
+    let mut points = vec![];
+    for i in 0..1000 {
+        points.push((list.add_element(i, rng.gen_range(1..100i64)), list.end()));
+    }
+
+    for _ in 0..1000 {
+        let index = rng.gen_range(1..points.len() - 1);
+        let distance = rng.gen_range(0..200);
+        let (key, _) = points[index];
+        let (start, old_distance) = if rng.gen() {
+            (index + 1, list.set_distance_to_next(key, distance).unwrap())
+        } else {
+            (index, list.set_distance_to_prev(key, distance).unwrap())
+        };
+        let delta = distance - old_distance;
+        assert_eq!(old_distance, points[start].1 - points[start - 1].1);
+        for (_, position) in &mut points[start..] {
+            *position += delta;
+        }
+        assert_eq!(list.position(key), Some(points[index].1));
+    }
+    check_invariants(&list);
+
+    assert_eq!(list.start(), points[0].1);
+    assert_eq!(list.end(), points.last().unwrap().1);
+    for &(key, position) in &points {
+        assert_eq!(list.position(key), Some(position));
+    }
+
+    let (first, _) = points[0];
+    let (last, _) = *points.last().unwrap();
+    assert_eq!(list.set_distance_to_prev(first, 1), Err(ShiftError::NoNeighbour));
+    assert_eq!(list.set_distance_to_next(last, 1), Err(ShiftError::NoNeighbour));
+    assert_eq!(list.set_distance_to_next(first, -1), Err(ShiftError::NegativeDistance));
+    assert_eq!(list.set_distance_to_prev(last, -1), Err(ShiftError::NegativeDistance));
+    list.remove_element(first);
+    assert_eq!(list.set_distance_to_next(first, 1), Err(ShiftError::UnknownKey));
+}
+
+#[test]
+fn test_split_off() {
+    let mut rng = StdRng::seed_from_u64(14);
+    for len in [0, 1, 2, 5, 6, 30, 500] {
+        for _ in 0..20 {
+            let (mut list, points) = random_gaps(&mut rng, 0..len);
+            let mut reference: BTreeMap<_, _> = points.into_iter().map(|(key, position, _)| (position, key)).collect();
+
+            let position = rng.gen_range(list.start() - 5..=list.end() + 5);
+            let mut split_off = list.split_off(position);
+            let split_off_reference = reference.split_off(&position);
+            check_invariants(&list);
+            check_invariants(&split_off);
+
+            // both halves keep the keys and elements of their points
+            assert_eq!(list.iter().map(|(key, position, _)| (position, key)).collect_vec(),
+                       reference.iter().map(|(&position, &key)| (position, key)).collect_vec());
+            assert_eq!(split_off.iter().map(|(key, position, _)| (position, key)).collect_vec(),
+                       split_off_reference.iter().map(|(&position, &key)| (position, key)).collect_vec());
+            for (i, &key) in reference.values().chain(split_off_reference.values()).enumerate() {
+                let (owner, other) = if i < reference.len() { (&list, &split_off) } else { (&split_off, &list) };
+                assert_eq!(owner.element(key), Some(&i));
+                assert_eq!(other.position(key), None);
+            }
+
+            check_still_usable(&mut rng, &mut list);
+            check_still_usable(&mut rng, &mut split_off);
+            for (&position, &key) in &split_off_reference {
+                assert_eq!(split_off.position(key), Some(position));
+            }
+        }
+    }
+}
+
+#[test]
+fn test_append() {
+    let mut rng = StdRng::seed_from_u64(15);
+    let lens = [0, 1, 2, 5, 6, 30, 126, 500];
+    for len in lens {
+        for other_len in lens {
+            let (mut list, mut reference) = random_gaps(&mut rng, 0..len);
+            let (other, other_points) = random_gaps(&mut rng, len..len + other_len);
+
+            let gap = rng.gen_range(1..20);
+            let shift = if len == 0 { 0 } else { list.end() + gap - other.start() };
+            let new_keys = list.append(other, gap).unwrap();
+            check_invariants(&list);
+
+            assert_eq!(new_keys.len(), other_len);
+            for (key, position, element) in other_points {
+                reference.push((new_keys[key], position + shift, element));
+            }
+            assert_eq!(list.iter().map(|(key, position, &element)| (key, position, element)).collect_vec(), reference);
+
+            check_still_usable(&mut rng, &mut list);
+        }
+    }
+}
+
+#[test]
+fn test_append_negative_gap() {
+    let (mut list, [a, b]) = list_from_array([('a', 2), ('b', 5)]);
+    let (other, _) = list_from_array([('c', 1), ('d', 3)]);
+    assert_eq!(list.append(other, -1), Err(InsertError::NegativeDistance));
+    check_invariants(&list);
+    assert_eq!(list.iter().collect_vec(), vec![(a, 2, &'a'), (b, 5, &'b')]);
+    let (other, _) = list_from_array([('c', 1)]);
+    assert_eq!(PointList::new().append(other, -1), Err(InsertError::NegativeDistance));
+}
+
+#[test]
+fn test_from_sorted() {
+    let mut rng = StdRng::seed_from_u64(16);
+    for len in [0, 1, 2, 5, 6, 25, 26, 126, 1000] {
+        let mut position = rng.gen_range(-100..100i64);
+        let mut points = vec![];
+        for i in 0..len {
+            points.push((position, i));
+            position += rng.gen_range(1..20);
+        }
+
+        let (mut list, keys) = PointList::from_sorted(points.clone());
+        check_invariants(&list);
+        // the frames are built with room for another point
+        assert!(list.index.frames.values().all(|frame| frame.len() < FRAME_CAPACITY));
+        if len > 1 {
+            let frames = list.index.frames.len();
+            list.insert_element(len, points[len / 2].0);
+            assert_eq!(list.index.frames.len(), frames);
+            list.remove_element(list.nth(len / 2 + 1).unwrap());
+            check_invariants(&list);
+        }
+        assert_eq!(keys.len(), len);
+        assert_eq!(list.iter().map(|(key, position, &element)| (key, position, element)).collect_vec(),
+                   keys.iter().zip(&points).map(|(&key, &(position, element))| (key, position, element)).collect_vec());
+
+        let distances = points.iter().scan(0, |previous, &(position, element)| {
+            let distance = position - *previous;
+            *previous = position;
+            Some((distance, element))
+        });
+        let (list_from_distances, _) = PointList::from_distances(distances);
+        assert_eq!(list_from_distances.iter().map(|(_, position, &element)| (position, element)).collect_vec(), points);
+
+        // the list remains usable
+        let mut reference = keys.iter().zip(&points).map(|(&key, &(position, _))| (position, key)).collect::<BTreeMap<_, _>>();
+        for _ in 0..200 {
+            if rng.gen() && !reference.is_empty() {
+                let &position = reference.keys().nth(rng.gen_range(0..reference.len())).unwrap();
+                let key = reference.remove(&position).unwrap();
+                list.remove_element(key);
+            } else {
+                let position = rng.gen_range(-200..20_000);
+                reference.entry(position).or_insert_with(|| list.insert_element(0, position));
+            }
+        }
+        check_invariants(&list);
+        assert_eq!(list.iter().map(|(key, position, _)| (position, key)).collect_vec(), reference.into_iter().collect_vec());
+    }
+}
+
+#[test]
+#[should_panic(expected = "must be sorted")]
+fn test_from_sorted_unsorted() {
+    PointList::from_sorted([(0, 'a'), (2, 'b'), (1, 'c')]);
+}
+
+#[test]
+#[should_panic(expected = "must be sorted")]
+fn test_from_sorted_unsorted_unsigned() {
+    PointIndex::from_sorted([0u32, 2, 1]);
+}
+
+#[test]
+fn test_last_base_frame_cache() {
+    let mut rng = StdRng::seed_from_u64(17);
+    let mut list = PointList::new();
+    let mut keys = vec![];
+    for round in 0..50 {
+        for _ in 0..rng.gen_range(0..100) {
+            keys.push(list.add_element(round, rng.gen_range(1..10i64)));
+            assert_eq!(list.last_key(), keys.last().copied());
+        }
+        check_invariants(&list);
+
+        // removing from the back merges the frames on the right spine
+        for _ in 0..rng.gen_range(0..=keys.len().min(80)) {
+            let index = if rng.gen() { keys.len() - 1 } else { rng.gen_range(0..keys.len()) };
+            list.remove_element(keys.remove(index));
+            assert_eq!(list.last_key(), keys.last().copied());
+        }
+        check_invariants(&list);
+
+        if !keys.is_empty() {
+            let key = keys[rng.gen_range(0..keys.len())];
+            list.insert_after(key, 1, round).ok();
+            let new_last = list.insert_element(round, list.end() + 1);
+            keys = list.keys().collect_vec();
+            assert_eq!(list.last_key(), Some(new_last));
+            check_invariants(&list);
+        }
+    }
+}
+
+#[test]
+fn test_add_element_negative_distance() {
+    let (mut list, [a]) = list_from_array([('a', 2)]);
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        list.add_element('b', -1);
+    }));
+    assert!(result.is_err());
+    // the list is left as it was
+    check_invariants(&list);
+    assert_eq!(list.len(), 1);
+    assert_eq!(list.iter().collect_vec(), vec![(a, 2, &'a')]);
+}
+
+#[test]
+fn test_retain() {
+    let mut rng = StdRng::seed_from_u64(18);
+    for len in [0, 1, 2, 6, 30, 1000] {
+        for keep_probability in [0.0, 0.1, 0.5, 0.9, 1.0] {
+            let (mut list, mut reference) = random_gaps(&mut rng, 0..len);
+
+            let mut visited = vec![];
+            list.retain(|key, position, element| {
+                visited.push((key, position, *element));
+                *element += 1;
+                rng.gen_bool(keep_probability)
+            });
+            assert_eq!(visited, reference);
+
+            let removed: Vec<_> = visited.iter().filter(|&&(key, _, _)| list.position(key).is_none()).collect();
+            reference.retain(|&(key, _, _)| list.position(key).is_some());
+            check_invariants(&list);
+            assert_eq!(removed.len() + reference.len(), len);
+            for &&(key, _, _) in &removed {
+                assert_eq!(list.element(key), None);
+            }
+            assert_eq!(list.iter().map(|(key, position, &element)| (key, position, element)).collect_vec(),
+                       reference.iter().map(|&(key, position, element)| (key, position, element + 1)).collect_vec());
+
+            check_still_usable(&mut rng, &mut list);
+        }
+    }
+}
+
+#[test]
+fn test_retain_panicking() {
+    let (mut list, keys) = PointList::from_sorted((0..100).map(|i| (i * 2, i)));
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        list.retain(|_, position, _| {
+            assert!(position < 50);
+            position % 4 == 0
+        });
+    }));
+    assert!(result.is_err());
+
+    // no point was removed, as the panic happened before the removals
+    check_invariants(&list);
+    assert_eq!(list.keys().collect_vec(), keys);
+    assert_eq!(list.elements().copied().collect_vec(), (0..100).collect_vec());
+
+    let (mut index, keys) = PointIndex::from_sorted(0..100);
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        index.retain(|_, position| {
+            assert!(position < 50);
+            position % 2 == 0
+        });
+    }));
+    assert!(result.is_err());
+    check_index_invariants(&index);
+    assert_eq!(index.keys().collect_vec(), keys);
+}
+
+#[test]
+fn test_drain_range() {
+    let mut rng = StdRng::seed_from_u64(19);
+    for len in [0, 1, 2, 6, 30, 500] {
+        for _ in 0..40 {
+            let (mut list, reference) = random_gaps(&mut rng, 0..len);
+
+            let start = rng.gen_range(list.start() - 5..=list.end() + 5);
+            let end = rng.gen_range(start..=list.end() + 5);
+            let range = [
+                (Bound::Included(start), Bound::Included(end)),
+                (Bound::Included(start), Bound::Excluded(end)),
+                (Bound::Excluded(start), Bound::Included(end)),
+                (Bound::Unbounded, Bound::Excluded(end)),
+                (Bound::Included(start), Bound::Unbounded),
+                (Bound::Unbounded, Bound::Unbounded),
+            ][rng.gen_range(0..6)];
+            let gap = if rng.gen() { RangeGap::Preserve } else { RangeGap::Collapse };
+
+            let drained = list.drain_range(range, gap).collect_vec();
+            check_invariants(&list);
+
+            let (expected, mut remaining): (Vec<_>, Vec<_>) =
+                reference.into_iter().partition(|(_, position, _)| range.contains(position));
+            assert_eq!(drained, expected);
+            if let (RangeGap::Collapse, Some(first), Some(last)) = (gap, expected.first(), expected.last()) {
+                // the range is cut out, but not beyond the points next to it
+                let bound = |bound: Bound<&i64>| match bound {
+                    Bound::Included(&bound) | Bound::Excluded(&bound) => Some(bound),
+                    Bound::Unbounded => None,
+                };
+                let range_start = match remaining.iter().rev().find(|(_, position, _)| *position < first.1) {
+                    Some(previous) => bound(range.start_bound()).unwrap_or(first.1).max(previous.1),
+                    None => first.1,
+                };
+                if let Some(next) = remaining.iter().find(|(_, position, _)| *position > last.1) {
+                    let shift = bound(range.end_bound()).unwrap_or(last.1).min(next.1) - range_start;
+                    for (_, position, _) in &mut remaining {
+                        if *position > last.1 {
+                            *position -= shift;
+                        }
+                    }
+                }
+            }
+            assert_eq!(list.iter().map(|(key, position, &element)| (key, position, element)).collect_vec(), remaining);
+            for (key, _, _) in drained {
+                assert_eq!(list.position(key), None);
+            }
+
+            check_still_usable(&mut rng, &mut list);
+            list.remove_range(.., RangeGap::Preserve);
+            check_invariants(&list);
+            assert!(list.is_empty());
+        }
+    }
+}
+
+#[test]
+fn test_drain_range_collapse() {
+    // a single removed point still has the extent of the range cut out
+    let (mut list, [a, _, c]) = list_from_array([('a', 4), ('b', 10), ('c', 20)]);
+    assert_eq!(list.drain_range(8..12, RangeGap::Collapse).map(|(_, position, _)| position).collect_vec(), vec![10]);
+    check_invariants(&list);
+    assert_eq!(list.position(a), Some(4));
+    assert_eq!(list.position(c), Some(16));
+
+    // a range wider than its points
+    let (mut list, [a, _, _, d]) = list_from_array([('a', 5), ('b', 12), ('c', 18), ('d', 30)]);
+    list.remove_range(10..20, RangeGap::Collapse);
+    check_invariants(&list);
+    assert_eq!(list.position(a), Some(5));
+    assert_eq!(list.position(d), Some(20));
+
+    // the range is clamped to the neighbouring points
+    let (mut list, [a, _, c]) = list_from_array([('a', 5), ('b', 12), ('c', 18)]);
+    list.remove_range(8..15, RangeGap::Collapse);
+    check_invariants(&list);
+    assert_eq!(list.position(a), Some(5));
+    assert_eq!(list.position(c), Some(11));
+    // without a point before the range, it starts at the first removed point
+    let (mut list, [_, b, _]) = list_from_array([('a', 5u32), ('b', 12), ('c', 18)]);
+    list.remove_range(..=8, RangeGap::Collapse);
+    check_invariants(&list);
+    assert_eq!(list.position(b), Some(9));
+    let (mut list, [_, _, c]) = list_from_array([('a', 5u32), ('b', 12), ('c', 18)]);
+    list.remove_range(0..15, RangeGap::Collapse);
+    check_invariants(&list);
+    assert_eq!(list.position(c), Some(8));
+}
+
+#[test]
+fn test_coincident_points() {
+    let mut rng = StdRng::seed_from_u64(20);
+    let mut list = PointList::new();
+    // (position, key) in positional order, with points at equal positions in insertion order
+    let mut reference: Vec<(i64, PointKey)> = vec![];
+    for i in 0..3000 {
+        match rng.gen_range(0..6) {
+            0 => {
+                let distance = rng.gen_range(0..2);
+                let key = list.add_element(i, distance);
+                let position = reference.last().map_or(distance, |&(position, _)| position + distance);
+                reference.push((position, key));
+            }
+            1 => {
+                let distance = rng.gen_range(0..2);
+                let key = list.push_front(i, distance).unwrap();
+                let position = reference.first().map_or(0, |&(position, _)| position - distance);
+                reference.insert(0, (position, key));
+            }
+            2 | 3 => {
+                let position = rng.gen_range(-20..20);
+                let key = list.insert_element(i, position);
+                let index = reference.partition_point(|&(other, _)| other <= position);
+                reference.insert(index, (position, key));
+            }
+            4 if !reference.is_empty() => {
+                let (_, key) = reference.remove(rng.gen_range(0..reference.len()));
+                list.remove_element(key);
+            }
+            _ if !reference.is_empty() => {
+                let index = rng.gen_range(0..reference.len());
+                let (position, key) = reference[index];
+                let new_position = rng.gen_range(-20..20);
+                assert_eq!(list.relocate(key, new_position), Some(position));
+                if new_position != position {
+                    reference.remove(index);
+                    let index = reference.partition_point(|&(other, _)| other <= new_position);
+                    reference.insert(index, (new_position, key));
+                }
+            }
+            _ => {}
+        }
+    }
+    check_invariants(&list);
+    assert_eq!(list.iter().map(|(key, position, _)| (position, key)).collect_vec(), reference);
+
+    for position in -30..30 {
+        let first = reference.iter().find(|&&(other, _)| other == position).map(|&(_, key)| key);
+        assert_eq!(list.key_at(position), first);
+        let at = reference.iter().filter(|&&(other, _)| other == position).map(|&(_, key)| key).collect_vec();
+        assert_eq!(list.range(position..=position).map(|(key, _, _)| key).collect_vec(), at);
+        assert_eq!(list.key_at_or_before(position).map(|(key, _)| key),
+                   reference.iter().rev().find(|&&(other, _)| other <= position).map(|&(_, key)| key));
+    }
+    for (index, &(_, key)) in reference.iter().enumerate() {
+        assert_eq!(list.nth(index), Some(key));
+        assert_eq!(list.index_of(key), Some(index));
+    }
+
+    // points at the same position stay together when splitting and are joined without a gap
+    let position = reference[reference.len() / 2].0;
+    let split_off = list.split_off(position);
+    assert!(split_off.positions().all(|other| other >= position));
+    assert!(list.positions().all(|other| other < position));
+    let end = list.end();
+    let new_keys = list.append(split_off, 0).unwrap();
+    check_invariants(&list);
+    assert_eq!(list.len(), reference.len());
+    assert!(new_keys.values().all(|&key| list.position(key).unwrap() >= end));
+
+    let key = list.first_key().unwrap();
+    assert_eq!(list.insert_after(key, 0, 0).map(|new_key| list.next_key(key) == Some(new_key)), Ok(true));
+    assert_eq!(list.insert_before(key, 0, 0).map(|new_key| list.prev_key(key) == Some(new_key)), Ok(true));
+    check_invariants(&list);
+}
+
+#[test]
+fn test_key_within() {
+    let (list, [a, b, c]) = list_from_array([('a', 4), ('b', 10), ('c', 12)]);
+    assert_eq!(list.key_within(4, 0), Some((a, 4)));
+    assert_eq!(list.key_within(6, 1), None);
+    assert_eq!(list.key_within(6, 2), Some((a, 4)));
+    assert_eq!(list.key_within(7, 3), Some((a, 4)));
+    assert_eq!(list.key_within(8, 3), Some((b, 10)));
+    assert_eq!(list.key_within(11, 5), Some((b, 10)));
+    assert_eq!(list.key_within(20, 8), Some((c, 12)));
+    assert_eq!(list.key_within(0, 3), None);
+}
+
+#[test]
+fn test_float_positions() {
+    assert_eq!(FloatPosition::new(f64::NAN), None);
+    assert!(FloatPosition::new(-0.5f32).unwrap() < FloatPosition::new(0.0).unwrap());
+    let hashed: HashSet<_> = [0.0, -0.0, 1.5, 1.5].map(|value| FloatPosition::new(value).unwrap()).into();
+    assert_eq!(hashed.len(), 2);
+
+    let position = |value: f64| FloatPosition::new(value).unwrap();
+    let mut list = PointList::new();
+    let mut keys = vec![];
+    for i in 0..1000 {
+        keys.push(list.add_element(i, position(0.1)));
+    }
+    // move the points back and forth to accumulate rounding errors
+    for (i, &key) in keys.iter().enumerate().rev() {
+        if i % 3 == 0 {
+            list.shift_after(key, position(0.7)).unwrap();
+            list.shift_after_signed(key, position(-0.7)).unwrap();
+        }
+    }
+    // the invariants can't be checked, as they compare positions that were summed up differently
+    let last_position = list.position(*keys.last().unwrap()).unwrap();
+    assert!((list.end() - last_position).get().abs() <= 1e-9);
+
+    let tolerance = position(1e-9);
+    for (i, &key) in keys.iter().enumerate() {
+        let expected = position((i + 1) as f64 / 10.0);
+        let (found, found_position) = list.key_within(expected, tolerance).unwrap();
+        assert_eq!(found, key);
+        assert!((found_position - expected).get().abs() <= 1e-9);
+    }
+    assert_eq!(list.key_within(position(0.05), position(0.01)), None);
+}
+
+#[test]
+fn test_float_positions_never_negative() {
+    let position = |value: f64| FloatPosition::new(value).unwrap();
+    let mut rng = StdRng::seed_from_u64(21);
+    let mut list = PointList::new();
+    let mut keys = vec![];
+    for i in 0..300 {
+        keys.push(list.add_element(i, position(rng.gen_range(0.0..1.0))));
+    }
+    // closing gaps exactly leaves rounding errors in the sums of the distances, which may not
+    // make any distance negative
+    for _ in 0..3000 {
+        let key = keys[rng.gen_range(0..keys.len())];
+        let distance = list.distance_to_next(key);
+        match rng.gen_range(0..3) {
+            0 => {
+                if let Some(distance) = distance {
+                    list.shift_after_signed(key, position(-distance.get())).unwrap();
+                }
+            }
+            1 => {
+                if distance.is_some() {
+                    list.set_distance_to_next(key, position(0.0)).unwrap();
+                }
+            }
+            _ => list.shift_after(key, position(rng.gen_range(0.0..1.0))).unwrap(),
+        }
+    }
+    for &key in &keys {
+        if let Some(distance) = list.distance_to_next(key) {
+            assert!(distance >= position(0.0));
+        }
+        if let Some(distance) = list.distance_to_prev(key) {
+            assert!(distance >= position(0.0));
+        }
+    }
+    assert!(list.positions().is_sorted());
+}
+
+#[test]
+#[should_panic]
+fn test_float_position_nan_result() {
+    let infinity = FloatPosition::new(f64::INFINITY).unwrap();
+    let _ = infinity - infinity;
+}
+
+#[test]
+fn test_entry() {
+    let mut rng = StdRng::seed_from_u64(22);
+    let mut list = PointList::new();
+    let mut reference = BTreeMap::new();
+    for i in 0..3000 {
+        let position = rng.gen_range(-500..500i64);
+        match list.entry(position) {
+            Entry::Occupied(mut entry) => {
+                let &(key, element) = reference.get(&position).unwrap();
+                assert_eq!(entry.key(), key);
+                assert_eq!(entry.position(), position);
+                assert_eq!(*entry.get(), element);
+                if rng.gen() {
+                    *entry.get_mut() += 1;
+                    reference.insert(position, (key, element + 1));
+                } else {
+                    assert_eq!(entry.remove(), element);
+                    reference.remove(&position);
+                }
+            }
+            Entry::Vacant(entry) => {
+                assert!(!reference.contains_key(&position));
+                assert_eq!(entry.position(), position);
+                reference.insert(position, (entry.insert(i), i));
+            }
+        }
+    }
+    check_invariants(&list);
+    assert_eq!(list.iter().map(|(key, position, &element)| (position, key, element)).collect_vec(),
+               reference.iter().map(|(&position, &(key, element))| (position, key, element)).collect_vec());
+
+    let (mut list, [a, b]) = list_from_array([('a', 4), ('b', 6)]);
+    assert_eq!(list.entry(6).or_insert('x'), b);
+    let c = list.entry(5).or_insert_with(|| 'c');
+    let d = list.entry(2).or_insert('d');
+    let e = list.entry(9).or_insert('e');
+    let Entry::Occupied(entry) = list.entry(4) else { panic!() };
+    *entry.into_mut() = 'A';
+    check_invariants(&list);
+    assert_eq!(list.keys().collect_vec(), vec![d, a, c, b, e]);
+    assert_eq!(list.elements().copied().collect_vec(), vec!['d', 'A', 'c', 'b', 'e']);
+    assert_eq!(list.positions().collect_vec(), vec![2, 4, 5, 6, 9]);
+}
+
+#[test]
+fn test_cursor() {
+    let mut rng = StdRng::seed_from_u64(23);
+    let mut list = PointList::new();
+    for i in 0..500 {
+        list.add_element(i, rng.gen_range(0..10i64));
+    }
+    let points = list.iter().map(|(key, position, &element)| (key, position, element)).collect_vec();
+    let current = |cursor: &Cursor<_, _>| cursor.key().map(|key| (key, cursor.position().unwrap(), *cursor.element().unwrap()));
+
+    let mut cursor = list.cursor_front();
+    for &point in &points {
+        assert_eq!(current(&cursor), Some(point));
+        cursor.move_next();
+    }
+    assert_eq!(current(&cursor), None);
+    cursor.move_prev();
+    for &point in points.iter().rev() {
+        assert_eq!(current(&cursor), Some(point));
+        cursor.move_prev();
+    }
+    assert_eq!(current(&cursor), None);
+    cursor.move_prev();
+    assert_eq!(current(&cursor), points.last().copied());
+    cursor.move_next();
+    cursor.move_next();
+    assert_eq!(current(&cursor), points.first().copied());
+
+    for _ in 0..100 {
+        let position = rng.gen_range(-10..list.end() + 10);
+        cursor.seek(position);
+        assert_eq!(current(&cursor), points.iter().find(|&&(_, other, _)| other >= position).copied());
+    }
+
+    // a copy stays where it was when the original moves on
+    let mut cursor = list.cursor(points[100].0).unwrap();
+    let saved = cursor;
+    for _ in 0..50 {
+        cursor.move_next();
+    }
+    assert_eq!(current(&saved), Some(points[100]));
+    assert_eq!(current(&cursor), Some(points[150]));
+    cursor = saved;
+    cursor.move_prev();
+    assert_eq!(current(&cursor), Some(points[99]));
+    let mut ghost = list.cursor_back();
+    ghost.move_next();
+    let saved_ghost = ghost;
+    ghost.move_next();
+    assert_eq!(current(&saved_ghost), None);
+    assert_eq!(current(&ghost), points.first().copied());
+    // also with elements that aren't `Copy`
+    let (strings, [first, _]) = list_from_array([("a".to_string(), 1), ("b".to_string(), 2)]);
+    let mut cursor = strings.cursor_front();
+    let saved = cursor;
+    cursor.move_next();
+    assert_eq!(saved.key(), Some(first));
+    assert_eq!(cursor.element().map(String::as_str), Some("b"));
+
+    let (key, position, element) = points[rng.gen_range(0..points.len())];
+    assert_eq!(current(&list.cursor(key).unwrap()), Some((key, position, element)));
+    assert_eq!(current(&list.cursor_back()), points.last().copied());
+    assert_eq!(current(&PointList::<i64, i64>::new().cursor_front()), None);
+}
+
+#[test]
+fn test_cursor_mut() {
+    let mut rng = StdRng::seed_from_u64(24);
+    let mut list = PointList::new();
+    let mut reference = vec![];
+    for i in 0..200 {
+        let key = list.add_element(i, rng.gen_range(1..10i64));
+        reference.push((key, list.end(), i));
+    }
+
+    let mut cursor = list.cursor_front_mut();
+    let mut index: usize = 0;
+    for i in 200..5000 {
+        match rng.gen_range(0..8) {
+            0 | 1 => {
+                cursor.move_next();
+                index += 1;
+            }
+            2 => {
+                cursor.move_prev();
+                index = index.checked_sub(1).unwrap_or(reference.len());
+            }
+            3 if index < reference.len() => {
+                let (key, position, _) = reference[index];
+                let limit = reference.get(index + 1).map_or(10, |&(_, next, _)| next - position);
+                let distance = rng.gen_range(0..=limit);
+                let new_key = cursor.insert_after(distance, i).unwrap();
+                reference.insert(index + 1, (new_key, position + distance, i));
+                assert_eq!(cursor.key(), Some(key));
+            }
+            4 if index < reference.len() => {
+                let (key, position, _) = reference[index];
+                let limit = index.checked_sub(1).map_or(10, |previous| position - reference[previous].1);
+                let distance = rng.gen_range(0..=limit);
+                let new_key = cursor.insert_before(distance, i).unwrap();
+                reference.insert(index, (new_key, position - distance, i));
+                index += 1;
+                assert_eq!(cursor.key(), Some(key));
+            }
+            5 if index < reference.len() => {
+                let (key, _, element) = reference.remove(index);
+                assert_eq!(cursor.remove_current(), Some((key, element)));
+            }
+            6 if index + 1 < reference.len() => {
+                let distance = rng.gen_range(0..10);
+                let old_distance = cursor.set_distance_to_next(distance).unwrap();
+                assert_eq!(old_distance, reference[index + 1].1 - reference[index].1);
+                for (_, position, _) in &mut reference[index + 1..] {
+                    *position += distance - old_distance;
+                }
+            }
+            7 if index > 0 && index < reference.len() => {
+                let distance = rng.gen_range(0..10);
+                let old_distance = cursor.set_distance_to_prev(distance).unwrap();
+                assert_eq!(old_distance, reference[index].1 - reference[index - 1].1);
+                for (_, position, _) in &mut reference[index..] {
+                    *position += distance - old_distance;
+                }
+            }
+            _ => {}
+        }
+        if index > reference.len() {
+            index = 0;
+        }
+        if let Some(element) = cursor.element_mut() {
+            *element += 1;
+            reference[index].2 += 1;
+        }
+
+        let expected = reference.get(index).map(|&(key, position, _)| (key, position));
+        assert_eq!(cursor.key().zip(cursor.position()), expected);
+        assert_eq!(cursor.as_cursor().key(), expected.map(|(key, _)| key));
+    }
+
+    check_invariants(&list);
+    assert_eq!(list.iter().map(|(key, position, &element)| (key, position, element)).collect_vec(), reference);
+}
+
+#[test]
+fn test_get_disjoint_mut() {
+    let (mut list, [a, b, c]) = list_from_array([('a', 4), ('b', 6), ('c', 9)]);
+
+    let [a_element, c_element] = list.get_disjoint_mut([a, c]).unwrap();
+    std::mem::swap(a_element, c_element);
+    assert_eq!(list.elements().copied().collect_vec(), vec!['c', 'b', 'a']);
+
+    assert!(list.get_disjoint_mut([a, b, a]).is_none());
+    list.remove_element(b);
+    assert!(list.get_disjoint_mut([a, b]).is_none());
+    assert!(list.get_disjoint_mut::<0>([]).is_some());
+    assert_eq!(list.get_disjoint_mut([c]), Some([&mut 'a']));
+}
+
+#[test]
+fn test_point_index() {
+    let mut rng = StdRng::seed_from_u64(25);
+    let (mut index, keys) = PointIndex::from_sorted([2, 3, 3, 8]);
+    check_index_invariants(&index);
+    assert_eq!(index.iter().collect_vec(), keys.iter().copied().zip([2, 3, 3, 8]).collect_vec());
+
+    // the reference is ordered by position and then by insertion order
+    let mut reference: BTreeMap<(i32, usize), PointKey> = keys.into_iter().enumerate()
+        .map(|(order, key)| ((index.position(key).unwrap(), order), key))
+        .collect();
+    for order in 4..500 {
+        if rng.gen_bool(0.6) || reference.is_empty() {
+            let position = rng.gen_range(-50..50);
+            let key = index.insert_point(position);
+            reference.insert((position, order), key);
+        } else {
+            let (&(position, order), &key) = reference.iter().nth(rng.gen_range(0..reference.len())).unwrap();
+            if rng.gen_bool(0.5) {
+                assert_eq!(index.remove_point(key), Some(position));
+            } else {
+                assert!(index.remove_key(key));
+            }
+            assert_eq!(index.remove_point(key), None);
+            assert!(!index.remove_key(key));
+            reference.remove(&(position, order));
+        }
+        check_index_invariants(&index);
+        assert_eq!(index.iter().collect_vec(), reference.iter().map(|(&(position, _), &key)| (key, position)).collect_vec());
+    }
+
+    let first = index.first_key().unwrap();
+    let before = index.push_front_point(3).unwrap();
+    let after = index.insert_point_after(first, 0).unwrap();
+    assert_eq!(index.prev_key(first), Some(before));
+    assert_eq!(index.next_key(first), Some(after));
+    let start = index.start();
+    let new_first = index.insert_point_before(before, 1).unwrap();
+    assert_eq!(index.position(new_first), Some(start - 1));
+
+    let mut split_off = index.split_off(0);
+    check_index_invariants(&index);
+    check_index_invariants(&split_off);
+    assert!(index.positions().all(|position| position < 0));
+    assert!(split_off.positions().all(|position| position >= 0));
+
+    split_off.retain(|_, position| position % 2 == 0);
+    assert_eq!(split_off.drain_range(..10, RangeGap::Preserve).map(|(_, position)| position).collect_vec(),
+               reference.keys().map(|&(position, _)| position).filter(|&position| (0..10).contains(&position) && position % 2 == 0).collect_vec());
+    let len = index.len() + split_off.len();
+    index.append(split_off, 1).unwrap();
+    check_index_invariants(&index);
+    assert_eq!(index.len(), len);
+    assert!(format!("{:?}", PointIndex::<i32>::new()).contains("PointIndex"));
+}